@@ -0,0 +1,23 @@
+// Generates the XML for `tests/out_dir.rs` into `OUT_DIR`, as a crate generating its
+// introspection at build time would.
+
+use std::{env, fs, path::PathBuf};
+
+const GENERATED_XML: &str = r#"<node>
+  <interface name="org.example.Generated">
+    <signal name="Generated">
+      <arg name="name" type="s"/>
+      <arg name="serial" type="u"/>
+    </signal>
+  </interface>
+</node>
+"#;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    let xml_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR should be set")).join("xml");
+    fs::create_dir_all(&xml_dir).expect("OUT_DIR should be writable");
+    fs::write(xml_dir.join("org.example.Generated.xml"), GENERATED_XML)
+        .expect("OUT_DIR should be writable");
+}
@@ -0,0 +1,2 @@
+//! Holds the build script that generates XML into `OUT_DIR` for `tests/out_dir.rs`, so the
+//! proc-macro crate itself needs no build script.
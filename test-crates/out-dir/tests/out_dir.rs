@@ -0,0 +1,11 @@
+// Tests for `xml` paths relative to `$OUT_DIR`, where `build.rs` generates XML.
+
+use zbus_lockstep_macros::validate;
+use zvariant::Type;
+
+#[validate(xml: "$OUT_DIR/xml")]
+#[derive(Debug, Type)]
+struct GeneratedEvent {
+    _name: String,
+    _serial: u32,
+}
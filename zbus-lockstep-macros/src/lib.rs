@@ -1,15 +1,42 @@
 //! # zbus-lockstep-macros
 //!
-//! This provides the `validate` macro that builds on `zbus-lockstep`.
+//! This provides the `validate` and `validate_from_manifest` macros, and the `Lockstep`
+//! derive, that build on `zbus-lockstep`.
 #![doc(html_root_url = "https://docs.rs/zbus-lockstep-macros/0.5.0")]
 
 type Result<T> = std::result::Result<T, syn::Error>;
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, LazyLock, Mutex},
+    time::SystemTime,
+};
 
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Token};
+use syn::{
+    ext::IdentExt, parse::ParseStream, parse_macro_input, Ident, Item, LitBool, LitStr, Token,
+};
+use zbus_xml::ArgDirection;
+
+// XML files selected by each `xml` path and file filter, keyed by the canonicalized path and the
+// filter, so each directory is read once per compilation, however many types are validated
+// against it. Each entry records the modification time and length of the files it was read
+// from, and is read again once they differ, as a long-lived proc-macro server, such as that of
+// an IDE, sees the files change.
+type XmlFiles = HashMap<PathBuf, String>;
+type FileStamps = Vec<(PathBuf, Option<SystemTime>, u64)>;
+type XmlFilesCache = HashMap<(PathBuf, Option<String>), (FileStamps, Arc<XmlFiles>)>;
+static XML_FILES_CACHE: LazyLock<Mutex<XmlFilesCache>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+// Parsed XML, keyed by file path and a hash of the contents, so each file is parsed once per
+// compilation. The contents are part of the key, as `trim_types` alters them before parsing.
+type NodeCache = HashMap<(PathBuf, u64), Arc<zbus_xml::Node<'static>>>;
+static XML_NODE_CACHE: LazyLock<Mutex<NodeCache>> = LazyLock::new(|| Mutex::new(HashMap::new()));
 
 /// Validate a struct's type signature against XML signal body type.
 ///
@@ -17,17 +44,62 @@ use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Toke
 /// struct's type signature.
 ///
 /// If the XML file(s) are found in the default location, `xml/` or `XML/` of the crate root,
-/// or provided as environment variable, `LOCKSTEP_XML_PATH`, the macro can be used without
+/// or provided as environment variable, `ZBUS_LOCKSTEP_XML_PATH`, the macro can be used without
 /// arguments.
 ///
+/// The type must derive `Type`, listed among its own attributes. Without such a derive, the
+/// macro fails at the type's name rather than in the generated test. A hand-written `Type`
+/// implementation is not recognized.
+///
+/// Enums that derive `Type` are validated the same way as structs. A newtype, such as
+/// `struct Volume(f64)`, has the signature of the value it wraps, so it matches a body of a
+/// single `d`.
+///
 ///
 /// # Arguments
 ///
-/// `#[validate]` can take three optional arguments:
+/// `#[validate]` can take the following optional arguments:
 ///
-/// * `xml`: Path to XML file(s) containing the signal definition.
+/// * `xml`: Path, list of paths or glob pattern of XML file(s) containing the signal definition.
+///   Files ending in `.xml.gz` are decompressed, which needs the `gzip` feature.
+/// * `xml_str`: The XML itself, instead of a path.
+/// * `file_filter`: Filename pattern of the XML files to consider.
 /// * `interface`: Interface name of the signal.
+/// * `prefix`: Common prefix of interface names, which `interface` may then omit.
+/// * `interface_const`: Path to a `&str` constant holding the interface name.
 /// * `signal`: Signal name.
+/// * `member`: Name of a signal, method or property, whichever XML declares.
+/// * `arg`: Index or name of the one argument to validate against.
+/// * `skip_fields`: Fields of the struct that are not sent, left out of the comparison.
+/// * `match`: Match signal names `"contains"`, the default, `"exact"` or `"suffix"`.
+/// * `test_name`: Name of the generated test.
+/// * `mode`: Generate a `"test"`, the default, or a `"fn"` returning the mismatch.
+/// * `test_attr`: Attribute to mark the generated test with instead of `test`.
+/// * `ignore`: Mark the generated test `#[ignore]`.
+/// * `emit_const`: Also record the interface and member on the type as associated constants.
+/// * `member_type`: Validate against a `"signal"`, the default, or a `"method"`'s in-args.
+/// * `method`: Method name, implies `member_type: "method"`.
+/// * `property`: Property name, validates against the property's type instead.
+/// * `direction`: Validate against a method's `"in"`-args, the default, or `"out"`-args.
+/// * `first_match`: Pick the first of several matching signals instead of failing.
+/// * `all_matches`: Validate against every matching signal instead of failing.
+/// * `on_missing`: Fail with an `"error"`, the default, or `"skip"` if no signal matches.
+/// * `exclusive`: Fail to compile if another type in the crate claims the same signal.
+/// * `strict_wrapping`: Do not match a struct against flat signal arguments.
+/// * `allow_trailing_metadata`: Permit one trailing `a{sv}` argument the struct omits.
+/// * `allow_value_fields`: Let `v` fields match any type in XML.
+/// * `allow_extra_trailing`: Permit trailing struct fields the signal omits.
+/// * `order`: Compare fields in `"strict"` order, the default, or in `"any"` order.
+/// * `print`: Print both signatures to stderr before comparing them.
+/// * `double_check`: Also compare the XML to a stored snapshot.
+/// * `signal_signature_annotation`: Take the expected signature from a signal annotation.
+/// * `trim_types`: Strip whitespace from `type` attributes in XML.
+/// * `validate_dtd`: Check the XML against the introspection DTD, needs the `dtd` feature.
+/// * `bus`, `service`, `path`: Introspect a running service instead of reading XML, needs the
+///   `live-introspection` feature.
+/// * `require_serde`: Also assert the type implements `Serialize` and `Deserialize`.
+/// * `require_arg_names`: Also assert all arguments of the signal are named in XML.
+/// * `signature`: Compare against a literal signature instead of XML.
 ///
 /// `#[validate(xml: <xml_path>, interface: <interface_name>, member: <member_name>)]`
 ///
@@ -47,14 +119,102 @@ use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Toke
 /// }
 /// ```
 ///
+/// The path may also be that of a single XML file, e.g. `xml: "xml/org.example.Node.xml"`.
+///
+/// Definitions spread over several directories can be listed together. Their files are
+/// considered as one set, so a signal found in more than one still needs disambiguation:
+///
+/// ```ignore
+/// #[validate(xml: ["xml/core", "../shared/xml"])]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// To select files among other XML, `xml` also takes a glob pattern:
+///
+/// ```ignore
+/// #[validate(xml: "xml/**/org.freedesktop.*.xml")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// A path containing `*` or `?` is taken as a pattern. Within a path component, `*` matches
+/// any sequence of characters and `?` any single character. A component of just `**` matches
+/// any number of directories, including none. Character classes, `[...]`, are not supported.
+/// Only files with an `.xml` extension are considered.
+///
+/// XML generated by a build script can be referred to relative to the crate's `OUT_DIR`. A
+/// leading `$OUT_DIR` is replaced by its value when the macro expands:
+///
+/// ```ignore
+/// #[validate(xml: "$OUT_DIR/xml")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// `ZBUS_LOCKSTEP_XML_PATH`, see below, takes precedence over a path relative to `$OUT_DIR`
+/// as over any other path argument, so the generated XML is not read while it is set.
 ///
 /// Alternatively, you can provide the XML directory path as environment variable,
-/// `LOCKSTEP_XML_PATH`, which will override both default and the path argument.
+/// `ZBUS_LOCKSTEP_XML_PATH`, which will override both default and the path argument. Its
+/// earlier name, `LOCKSTEP_XML_PATH`, is read if it is not set. Like `PATH`, it can list
+/// several directories, separated by `:`, or `;` on Windows, which are considered as one set.
+/// Each listed directory must exist.
+///
+/// ## `xml_str`
+///
+/// For small, self-contained examples, the introspection XML can be given inline instead. No
+/// files are looked up then, neither in the default location nor in `ZBUS_LOCKSTEP_XML_PATH`.
+///
+/// ```ignore
+/// #[validate(xml_str: r#"
+///     <node>
+///       <interface name="org.example.Node">
+///         <signal name="RemoveNode">
+///           <arg name="name" type="s"/>
+///           <arg name="path" type="o"/>
+///         </signal>
+///       </interface>
+///     </node>
+/// "#)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// `xml_str` cannot be combined with `xml`.
+///
+/// ## `file_filter`
+///
+/// If the XML directory also holds unrelated XML files, such as configuration, you can
+/// restrict which files are considered with a filename pattern. `*` matches any sequence of
+/// characters and `?` matches any single character. Files that do not match are skipped.
+///
+/// ```ignore
+/// #[validate(xml: "xml", file_filter: "*.interface.xml")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
 ///
 /// ## `interface`
 ///
 /// If more than one signal with the same name is defined in the XML file(s),
 /// the macro will fail and you can provide an interface name to disambiguate.
+/// The error lists every interface and file that matched.
 ///
 /// ```ignore
 /// #[validate(interface: "org.example.Node")]
@@ -65,10 +225,59 @@ use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Toke
 /// }
 /// ```
 ///
+/// Together with `signal`, exactly that signal of that interface is used. The macro fails if
+/// the interface is not found, or the interface has no signal of that name.
+///
+/// Signals with the same name and signature, such as the same interface XML vendored twice,
+/// are not ambiguous and need no disambiguation.
+///
+/// If the interface declares only one signal, that signal is used, whether or not its name
+/// is contained in the struct's name.
+///
+///
+/// ## `prefix`
+///
+/// When all interfaces share a prefix, `interface` may name them without it. With a `prefix`,
+/// `interface` matches an interface whose name is the prefix followed by `interface`, as well
+/// as one of exactly that name.
+///
+/// ```ignore
+/// #[validate(prefix: "org.mpris.MediaPlayer2.", interface: "Player", signal: "Seeked")]
+/// #[derive(Type)]
+/// struct SeekedSignal {
+///    position: i64,
+/// }
+/// ```
+///
+/// `prefix` needs `interface`.
+///
+/// ## `interface_const`
+///
+/// If the interface name is already defined as a constant, you can refer to that constant
+/// instead of repeating the literal.
+///
+/// ```ignore
+/// const NODE_INTERFACE: &str = "org.example.Node";
+///
+/// #[validate(interface_const: NODE_INTERFACE)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// A procedural macro cannot evaluate constants, so the value is not known when the macro
+/// expands. Instead, the macro collects every interface that declares the signal and the
+/// generated test selects the one named by the constant. The constant must therefore be in
+/// scope at the struct's definition and evaluate to a `&str`.
+///
+/// `interface` and `interface_const` are mutually exclusive.
 ///
 /// ## `signal`
 ///
-/// If a custom signal name is desired, you can be provided using `signal:`.
+/// If a custom signal name is desired, you can be provided using `signal:`. The struct's name
+/// is then not considered, even if it contains the name of another signal.
 ///
 /// ```ignore
 /// #[validate(signal: "RemoveNode")]
@@ -79,254 +288,4184 @@ use syn::{parse::ParseStream, parse_macro_input, Ident, ItemStruct, LitStr, Toke
 /// }
 /// ```
 ///
-/// ## Multiple arguments
+/// ## `member`
 ///
-/// You can provide multiple arguments with a comma separated list.
+/// `member:` names a member of any kind. Unless `member_type` is given, the macro looks the
+/// name up in XML and validates against the signal, method in-args or property of that name,
+/// preferring a signal over a method and a method over a property. `interface` restricts the
+/// search as for signals, so that a signal and a method of the same name in different
+/// interfaces can be told apart.
 ///
-/// # Examples
+/// ```ignore
+/// #[validate(interface: "org.example.Control", member: "Refresh")]
+/// #[derive(Type)]
+/// struct RefreshRequest {
+///    delay: u32,
+/// }
+/// ```
+///
+/// With live introspection, `member` names a signal unless `member_type` says otherwise.
+///
+/// ## `arg`
+///
+/// A struct may model only one of several arguments of a signal. With `arg:`, a zero-based
+/// index or the name of an argument in XML, the struct is compared to that argument alone
+/// rather than to the whole body.
 ///
 /// ```ignore
-/// #[validate(xml: "xml", interface: "org.example.Node", signal: "RemoveNode")]
+/// #[validate(signal: "PropertiesChanged", arg: "changed_properties")]
 /// #[derive(Type)]
+/// struct ChangedProperties(HashMap<String, OwnedValue>);
+/// ```
+///
+/// `arg` also applies to the arguments of methods, but not to properties.
+///
+/// ## `skip_fields`
+///
+/// A struct may carry client-side fields that are not part of the message, such as a cache
+/// timestamp skipped by serde. The derived `Type` still includes them. `skip_fields` lists them
+/// by name, and the type is compared as if it had only its other fields, in order.
+///
+/// ```ignore
+/// #[validate(signal: "RemoveNode", skip_fields: ["cached_at"])]
+/// #[derive(Type, Serialize)]
 /// struct RemoveNodeSignal {
 ///    name: String,
 ///    path: OwnedObjectPath,
+///    #[serde(skip)]
+///    cached_at: u64,
 /// }
 /// ```
-#[proc_macro_attribute]
-pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
-    // Parse the macro arguments.
-    let args = parse_macro_input!(args as ValidateArgs);
-
-    // Parse the item struct.
-    let item_struct = parse_macro_input!(input as ItemStruct);
-    let item_name = item_struct.ident.to_string();
-
-    let xml_str = args.xml.as_ref().and_then(|p| p.to_str());
-
-    let xml = match zbus_lockstep::resolve_xml_path(xml_str) {
-        Ok(xml) => xml,
-        Err(e) => {
-            return syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!("Failed to resolve XML path: {e}"),
-            )
-            .to_compile_error()
-            .into();
-        }
-    };
-
-    // Store each file's XML as a string in a with the XML's file path as key.
-    let mut xml_files: HashMap<PathBuf, String> = HashMap::new();
-    let read_dir = std::fs::read_dir(xml);
-
-    // If the path does not exist, the process lacks permissions to read the path,
-    // or the path is not a directory, return an error.
-    if let Err(e) = read_dir {
-        return syn::Error::new(
-            proc_macro2::Span::call_site(),
-            format!("Failed to read XML directory: {e}"),
-        )
-        .to_compile_error()
-        .into();
-    }
-
-    // Iterate over the directory and store each XML file as a string.
-    for entry in read_dir.expect("Failed to read XML directory") {
-        let entry = entry.expect("Failed to read XML file");
-
-        // Skip directories.
-        if entry.path().is_dir() {
-            continue;
-        }
-
-        if entry.path().extension().expect("File has no extension.") == "xml" {
-            let xml =
-                std::fs::read_to_string(entry.path()).expect("Unable to read XML file to string");
-            xml_files.insert(entry.path().clone(), xml);
-        }
-    }
-
-    // These are later needed to call `get_signal_body_type`.
-    let mut xml_file_path = None;
-    let mut interface_name = None;
-    let mut signal_name = None;
-
-    // Iterate over `xml_files` and find the signal that is contained in the struct's name.
-    // Or if `signal_arg` is provided, use that.
-    for (path_key, xml_string) in xml_files {
-        let node = zbus_xml::Node::try_from(xml_string.as_str());
-
-        if node.is_err() {
-            return syn::Error::new(
-                proc_macro2::Span::call_site(),
-                format!(
-                    "Failed to parse XML file: \"{}\" Err: {}",
-                    path_key.to_str().unwrap(),
-                    node.err().unwrap()
-                ),
-            )
-            .to_compile_error()
-            .into();
-        }
-
-        let node = node.unwrap();
-
-        for interface in node.interfaces() {
-            // We were called with an interface argument, so if the interface name does not match,
-            // skip it.
-            if args.interface.is_some()
-                && interface.name().as_str() != args.interface.as_ref().unwrap()
-            {
-                continue;
-            }
-
-            for signal in interface.signals() {
-                if args.signal.is_some() && signal.name().as_str() != args.signal.as_ref().unwrap()
-                {
-                    continue;
-                }
-
-                let xml_signal_name = signal.name();
-
-                if args.signal.is_some()
-                    && xml_signal_name.as_str() == args.signal.as_ref().unwrap()
-                {
-                    interface_name = Some(interface.name().to_string());
-                    signal_name = Some(xml_signal_name.to_string());
-                    xml_file_path = Some(path_key.clone());
-                    continue;
-                }
-
-                if item_name.contains(xml_signal_name.as_str()) {
-                    // If we have found a signal with the same name in an earlier iteration:
-                    if interface_name.is_some() && signal_name.is_some() {
-                        return syn::Error::new(
-                            proc_macro2::Span::call_site(),
-                            "Multiple interfaces with the same signal name. Please disambiguate.",
-                        )
-                        .to_compile_error()
-                        .into();
-                    }
-                    interface_name = Some(interface.name().to_string());
-                    signal_name = Some(xml_signal_name.to_string());
-                    xml_file_path = Some(path_key.clone());
-                }
-            }
-        }
-    }
-
-    // Lets be nice and provide a informative compiler error message.
-
-    // We searched all XML files and did not find a match.
-    if interface_name.is_none() {
-        return syn::Error::new(
-            proc_macro2::Span::call_site(),
-            format!(
-                "No interface matching signal name '{}' found.",
-                args.signal.unwrap_or_else(|| item_name.clone())
-            ),
-        )
-        .to_compile_error()
-        .into();
-    }
-
-    // If we did find a matching interface we have also set `xml_file_path` and `signal_name`.
-
-    let interface_name = interface_name.expect("Interface should have been found in search loop.");
-    let signal_name = signal_name.expect("Signal should have been found in search loop.");
-
-    let xml_file_path = xml_file_path.expect("XML file path should be found in search loop.");
-    let xml_file_path = xml_file_path
-        .to_str()
-        .expect("XML file path should be valid UTF-8");
-
-    // Create a block to return the item struct with a uniquely named validation test.
-    let test_name = format!("test_{item_name}_type_signature");
-    let test_name = Ident::new(&test_name, proc_macro2::Span::call_site());
-
-    let item_struct_name = item_struct.ident.clone();
-    let item_struct_name = Ident::new(
-        &item_struct_name.to_string(),
-        proc_macro2::Span::call_site(),
-    );
-
-    let item_plus_validation_test = quote! {
-        #item_struct
-
-        #[cfg(test)]
-        #[test]
-        fn #test_name() {
-            use zvariant::Type;
-
-            let xml_file = std::fs::File::open(#xml_file_path).expect("\"#xml_file_path\" expected to be a valid file path." );
-            let item_signature_from_xml = zbus_lockstep::get_signal_body_type(
-                xml_file,
-                #interface_name,
-                #signal_name,
-                None
-            ).expect("Failed to get signal body type from XML file.");
-            let item_signature_from_struct = <#item_struct_name as Type>::SIGNATURE;
-
-            assert_eq!(&item_signature_from_xml, item_signature_from_struct);
-        }
-    };
-
-    item_plus_validation_test.into()
-}
-
-struct ValidateArgs {
-    // Optional path to XML file
-    xml: Option<PathBuf>,
-
-    // Optional interface name
-    interface: Option<String>,
-
-    // Optional signal name
-    signal: Option<String>,
-}
-
-impl syn::parse::Parse for ValidateArgs {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let mut xml = None;
-        let mut interface = None;
-        let mut signal = None;
-
-        while !input.is_empty() {
-            let ident = input.parse::<Ident>()?;
-            match ident.to_string().as_str() {
-                "xml" => {
-                    input.parse::<Token![:]>()?;
-                    let lit = input.parse::<LitStr>()?;
-                    xml = Some(PathBuf::from(lit.value()));
-                }
-                "interface" => {
-                    input.parse::<Token![:]>()?;
-                    let lit = input.parse::<LitStr>()?;
-                    interface = Some(lit.value());
-                }
-                "signal" => {
-                    input.parse::<Token![:]>()?;
-                    let lit = input.parse::<LitStr>()?;
-                    signal = Some(lit.value());
-                }
-                _ => {
-                    return Err(syn::Error::new(
-                        ident.span(),
-                        format!("Unexpected argument: {ident}"),
-                    ))
-                }
-            }
-
-            if !input.is_empty() {
-                input.parse::<Token![,]>()?;
-            }
-        }
-
-        Ok(ValidateArgs {
-            xml,
-            interface,
-            signal,
-        })
+///
+/// This only adjusts the comparison, it does not change how the type is serialized. It applies
+/// to structs, but not to `validate_all!`, which does not see the fields of its types.
+///
+/// ## `match`
+///
+/// Without `signal:`, the struct is validated against the signal whose name its own name
+/// contains. A short signal name, such as `State`, is then also contained in unrelated names,
+/// such as `StateMachineConfig`. To rule that out, `match: "exact"` requires the struct's name
+/// to equal the signal's name, and `match: "suffix"` requires it to end with the signal's name.
+///
+/// ```ignore
+/// #[validate(match: "suffix")]
+/// #[derive(Type)]
+/// struct OnRemoveNode {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `test_name`
+///
+/// The generated test is named `test_<struct>_type_signature`. When structs in different
+/// modules share a name, or to target the test with `cargo test <name>`, you can name the
+/// test with `test_name:`. The name must be a valid Rust identifier.
+///
+/// ```ignore
+/// #[validate(test_name: "remove_node_matches_xml")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `signature`
+///
+/// Without introspection XML, but with a known signature, for instance from a specification,
+/// the type can be compared against the signature directly. No XML is read, so `signature`
+/// cannot be combined with `xml` or a member name.
+///
+/// ```ignore
+/// #[validate(signature: "(so)")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// A literal that is not a valid signature is a compile error.
+///
+/// ## `mode`
+///
+/// By default, the validation is a `#[test]` and only runs under `cargo test`. To run it from
+/// your own harness or a build-time check instead, `mode: "fn"` generates a function
+/// `pub fn __validate_<struct>() -> Result<(), String>`. It performs the same checks as the
+/// test, but returns the message of a failing check rather than panicking, so that many
+/// validations can be reported together. `test_name` names the function instead.
+///
+/// The leading underscores keep the name clear of the crate's own functions, but the name is
+/// part of the API: it is `__validate_` followed by the type's name, and only changes with a
+/// major version. It is a `zbus_lockstep::Validation`.
+///
+/// ```ignore
+/// #[validate(mode: "fn")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// if let Err(mismatch) = __validate_RemoveNodeSignal() {
+///     eprintln!("{mismatch}");
+/// }
+/// ```
+///
+/// A failing check returns its message without panicking, so nothing is printed and this
+/// works with `panic = "abort"` as well.
+///
+/// Unlike the test, the function is not gated on `cfg(test)`: it is compiled into every build,
+/// with the paths of the XML files it reads when called. To keep it out of non-test builds,
+/// apply the attribute in tests only, with `#[cfg_attr(test, validate(mode: "fn"))]`.
+///
+/// Listing the functions in `zbus_lockstep::aggregate_validations!` runs them all as a single
+/// test, which reports every failure together. With many types this keeps the test binary
+/// smaller and quicker to start than a test per type, at the cost of running or filtering one
+/// type on its own:
+///
+/// ```ignore
+/// zbus_lockstep::aggregate_validations!(lockstep_signatures, [__validate_RemoveNodeSignal]);
+/// ```
+///
+/// With a manifest, `validations` lists the functions for its aggregated test instead, see
+/// [`validate_from_manifest!`].
+///
+/// ## `test_attr`
+///
+/// The generated test is marked `#[test]`. To have it run by another harness, such as that of
+/// an async runtime, `test_attr` replaces the attribute with the given one, verbatim:
+///
+/// ```ignore
+/// #[validate(test_attr: "tokio::test")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// Only the attribute changes, the test itself stays synchronous. This does not apply to
+/// `mode: "fn"`.
+///
+/// ## `ignore`
+///
+/// To keep validations in the tree without running them on every `cargo test`, `ignore: true`
+/// marks the generated test `#[ignore = "lockstep validation"]`. Ignored tests are run with
+/// `cargo test -- --ignored`, for instance when syncing with a new version of the
+/// specification.
+///
+/// ```ignore
+/// #[validate(ignore: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// This does not apply to `mode: "fn"`.
+///
+/// ## `emit_const`
+///
+/// With `emit_const: true`, the type also records what it is validated against, for other
+/// code and documentation to refer to. The macro emits an `impl` block of its own with two
+/// associated constants, holding the names it resolved:
+///
+/// ```ignore
+/// #[validate(signal: "RemoveNode", emit_const: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// assert_eq!(RemoveNodeSignal::DBUS_INTERFACE, "org.example.Node");
+/// assert_eq!(RemoveNodeSignal::DBUS_MEMBER, "RemoveNode");
+/// ```
+///
+/// Other associated items of the type are unaffected, but it must not define constants of
+/// these names itself. With `interface_const`, `DBUS_INTERFACE` is that constant.
+/// `emit_const` cannot be combined with `signature` or `all_matches`, which do not resolve a
+/// single member.
+///
+/// ## `member_type`
+///
+/// Clients often model a method call's arguments as a single request struct.
+/// With `member_type: "method"`, the struct is validated against the in-args of a method
+/// instead of the body of a signal. The method is found by name like a signal is, and
+/// `member` may be used instead of `signal` to name it.
+///
+/// ```ignore
+/// #[validate(member_type: "method", member: "CreateNode")]
+/// #[derive(Type)]
+/// struct CreateNodeRequest {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// Like signal bodies, several flat in-args match a struct, unless `strict_wrapping` is set.
+/// `require_arg_names` only applies to signals.
+///
+/// ## `method` and `direction`
+///
+/// `method: "CreateNode"` is short for `member_type: "method", member: "CreateNode"`, and so
+/// cannot be combined with `signal`. Disambiguation across interfaces works as for signals.
+///
+/// By default, a method's in-args are validated. To validate a type modeling the reply of a
+/// method instead, select its out-args with `direction: "out"`.
+///
+/// ```ignore
+/// #[validate(method: "GetNode", direction: "out")]
+/// #[derive(Type)]
+/// struct GetNodeReply {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// Several out-args are compared in declaration order, like the fields of a struct or tuple
+/// struct. A single out-arg is compared as is, without wrapping.
+///
+/// ## `property`
+///
+/// Types modeling a property are validated against the property's type with
+/// `property: "Features"`. This cannot be combined with `signal` or `method`. Disambiguation
+/// across interfaces works as for signals.
+///
+/// ```ignore
+/// #[validate(property: "Features")]
+/// #[derive(Type)]
+/// struct Features(Vec<String>);
+/// ```
+///
+/// ## `first_match`
+///
+/// If the signal is declared by several interfaces and you know these definitions to be
+/// identical, `first_match: true` suppresses the ambiguity error. The macro then picks the
+/// first match, sorted by interface name and file path, so the choice is reproducible.
+///
+/// Note that this can mask genuine divergence between the definitions: only the first match
+/// is validated.
+///
+/// ```ignore
+/// #[validate(first_match: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `all_matches`
+///
+/// Where several signals share one body, such as the same signal declared by many interfaces,
+/// `all_matches: true` validates the type against each of them rather than failing on the
+/// ambiguity. The test fails if any of them differs, naming that signal and its interface.
+///
+/// ```ignore
+/// #[validate(signal: "Changed", all_matches: true)]
+/// #[derive(Type)]
+/// struct ChangedSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// `all_matches` cannot be combined with `first_match`, `interface_const` or live
+/// introspection.
+///
+/// ## `on_missing`
+///
+/// If no signal matches, the macro fails. For a signal that only some of the XML sets the
+/// source is compiled with declare, such as that of an optional feature, `on_missing: "skip"`
+/// leaves the struct unchanged and generates no test instead.
+///
+/// ```ignore
+/// #[validate(signal: "RemoveNode", on_missing: "skip")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// With `mode: "fn"`, no function is generated either.
+///
+/// ## `exclusive`
+///
+/// To catch a signal accidentally being modeled twice, `exclusive: true` claims the matched
+/// signal for the type. Compilation fails if another type in the same crate claims the same
+/// signal of the same interface, naming both types.
+///
+/// ```ignore
+/// #[validate(signal: "RemoveNode", exclusive: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeEvent {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// // Error[E0428]: the name `__lockstep_claim_org_example_Node__RemoveNode` is defined
+/// // multiple times, pointing at `RemoveNodeEvent` and `DeletionEvent`.
+/// #[validate(signal: "RemoveNode", exclusive: true)]
+/// #[derive(Type)]
+/// struct DeletionEvent {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// Each claim is a hidden `#[macro_export]` macro named after the interface and signal, so it
+/// lives at the crate root whichever module the type is in, and a second claim is a duplicate
+/// definition. Types that are not validated with `exclusive: true` are not taken into account.
+/// `exclusive` cannot be combined with `interface_const`, as the interface is not known when
+/// the macro expands.
+///
+/// ## `strict_wrapping`
+///
+/// A struct's signature is wrapped in parentheses, e.g. `(su)`, yet a signal body of two flat
+/// arguments, `s` and `u`, is not. By default the macro compares after removing this one
+/// level of wrapping, so a struct matches both a body of flat arguments and a body of a single
+/// struct argument:
+///
+/// ```xml
+/// <signal name="Flat">
+///   <arg name="name" type="s"/>
+///   <arg name="id" type="u"/>
+/// </signal>
+/// <signal name="Wrapped">
+///   <arg name="node" type="(su)"/>
+/// </signal>
+/// ```
+///
+/// With `strict_wrapping: true`, a struct only matches a body of a single struct argument,
+/// `Wrapped` above, and the generated test fails on a body of flat arguments.
+///
+/// ```ignore
+/// #[validate(signal: "Wrapped", strict_wrapping: true)]
+/// #[derive(Type)]
+/// struct WrappedSignal {
+///    name: String,
+///    id: u32,
+/// }
+/// ```
+///
+/// ## `allow_trailing_metadata`
+///
+/// Interfaces commonly evolve by appending an `a{sv}` "metadata" argument to a signal. With
+/// `allow_trailing_metadata: true`, the XML may declare exactly one argument more than the
+/// struct has fields, provided that argument is last and has signature `a{sv}`. The surplus
+/// argument is left out of the comparison.
+///
+/// This allowance is deliberately narrow: a trailing argument of any other type, or more than
+/// one surplus argument, still fails the test.
+///
+/// ```ignore
+/// #[validate(allow_trailing_metadata: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `allow_value_fields`
+///
+/// A field kept dynamic, e.g. as `zvariant::OwnedValue`, has signature `v`, while the XML may
+/// declare a concrete type at its position. With `allow_value_fields: true`, every `v` in the
+/// struct's signature matches whatever type the XML declares at that position. All other
+/// positions must still match exactly.
+///
+/// ```ignore
+/// #[validate(allow_value_fields: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedValue,
+/// }
+/// ```
+///
+/// ## `allow_extra_trailing`
+///
+/// A type may model a signal body plus some fields of its own, kept for convenience. With
+/// `allow_extra_trailing: true`, the validation passes as long as the struct's fields start
+/// with all the signal's arguments, in order. Any fields after those are ignored.
+///
+/// ```ignore
+/// #[validate(allow_extra_trailing: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+///    // Not sent over the bus.
+///    received: u32,
+/// }
+/// ```
+///
+/// Combined with `allow_value_fields`, the leading `v` fields may match any type in XML.
+/// Combined with `order: "any"`, the signal's arguments may be anywhere among the fields.
+///
+/// ## `order`
+///
+/// A struct's signature follows the declaration order of its fields. If that order differs
+/// from the order of the arguments in XML, but is semantically irrelevant, `order: "any"`
+/// compares the top-level fields as a multiset instead: every type must be present as often
+/// as in XML, in any order.
+///
+/// ```ignore
+/// #[validate(order: "any")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    path: OwnedObjectPath,
+///    name: String,
+/// }
+/// ```
+///
+/// Fields of nested structures are still compared in order.
+///
+/// ## `print`
+///
+/// To see the signatures being compared, whether the test fails or passes, `print: true`
+/// prints both to stderr before they are compared. The signature from XML is printed as
+/// found, before any adjustment by other arguments.
+///
+/// ```ignore
+/// #[validate(print: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// Run the test with `--nocapture` to see the output of a passing test.
+///
+/// ## `double_check`
+///
+/// With `double_check: true`, the generated test checks the XML as well as the type. Besides
+/// comparing the type to the XML, it compares the XML to a snapshot of the signature, stored in
+/// `lockstep_snapshots/<interface>.<signal>.signature` of the crate root. A changed type fails
+/// with "Type drifted from XML", a changed XML with "XML drifted from snapshot".
+///
+/// The first run records the snapshot. To accept a deliberate change to the XML, delete the
+/// snapshot file and run the test again.
+///
+/// ```ignore
+/// #[validate(double_check: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `signal_signature_annotation`
+///
+/// Some services document the true body signature of a signal in an annotation, for instance
+/// when the individual arguments are lossy:
+///
+/// ```xml
+/// <signal name="Changed">
+///   <annotation name="org.example.BodySignature" value="(sa{sv})"/>
+///   <arg name="change" type="v"/>
+/// </signal>
+/// ```
+///
+/// With `signal_signature_annotation: "org.example.BodySignature"`, the value of that
+/// annotation on the matched signal is the expected signature. Signals without the annotation
+/// fall back to the concatenation of their arguments.
+///
+/// ```ignore
+/// #[validate(signal_signature_annotation: "org.example.BodySignature")]
+/// #[derive(Type)]
+/// struct ChangedSignal {
+///    name: String,
+///    properties: HashMap<String, OwnedValue>,
+/// }
+/// ```
+///
+/// This only applies to signals and cannot be combined with `allow_trailing_metadata`.
+///
+/// ## `trim_types`
+///
+/// Hand-edited XML sometimes has stray whitespace in `type` attributes, e.g. `type=" s"`,
+/// which fails to parse as a signature. With `trim_types: true`, whitespace is stripped from
+/// `type` attributes before the XML is parsed. The type codes themselves are left untouched.
+///
+/// ```ignore
+/// #[validate(trim_types: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `validate_dtd`
+///
+/// As a quality gate, `validate_dtd: true` checks that the XML files conform to the `DBus`
+/// introspection DTD before any signatures are extracted. Structural problems, such as an
+/// `<arg>` without `type` or a `<signal>` outside of an `<interface>`, are reported as a
+/// compile error naming the file and line.
+///
+/// This requires the `dtd` feature, which is off by default.
+///
+/// ```ignore
+/// #[validate(validate_dtd: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `bus`, `service` and `path`
+///
+/// Rather than against checked-in XML, which may drift from the actual service, a type can be
+/// validated against the introspection of the running service itself. Given the `bus`,
+/// `"session"` or `"system"`, the well-known name of the `service` and the object `path`,
+/// the generated test calls `org.freedesktop.DBus.Introspectable.Introspect` on the object and
+/// validates against the XML it returns.
+///
+/// ```ignore
+/// #[validate(
+///     bus: "session",
+///     service: "org.example.Node",
+///     path: "/org/example/Node",
+///     interface: "org.example.Node",
+///     signal: "RemoveNode",
+/// )]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// As the XML is only known when the test runs, the `interface` and member name must be given.
+///
+/// This requires the `live-introspection` feature, which is off by default. When the test runs,
+/// `dbus-send` must be on `PATH`, the bus must be reachable, for the session bus through
+/// `DBUS_SESSION_BUS_ADDRESS`, and the service must be running or activatable. Otherwise the
+/// test fails.
+///
+/// ## `require_serde`
+///
+/// Types that are sent over the bus must also (de)serialize. With `require_serde: true`, the
+/// macro emits a compile-time assertion that the type implements `serde::Serialize` and
+/// `serde::Deserialize`. This catches types that derive `Type` but forgot the serde derives.
+///
+/// This is off by default, so types that only derive `Type` are not forced to depend on `serde`.
+///
+/// ```ignore
+/// #[validate(require_serde: true)]
+/// #[derive(Type, Serialize, Deserialize)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## `require_arg_names`
+///
+/// To keep the XML well-documented, `require_arg_names: true` makes the generated test fail
+/// if any argument of the matched signal lacks a `name` attribute. The failure reports the
+/// positions of the unnamed arguments. This is off by default.
+///
+/// ```ignore
+/// #[validate(require_arg_names: true)]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+///
+/// ## Multiple arguments
+///
+/// You can provide multiple arguments with a comma separated list.
+///
+/// # Generated test
+///
+/// The macro returns the type unchanged, followed by a test named
+/// `test_<type_name>_type_signature`. The test is gated behind `#[cfg(test)]`, so it is
+/// excluded from normal builds while the type itself is always available.
+///
+/// ```ignore
+/// #[validate]
+/// #[derive(Type)]
+/// struct AddNodeEvent {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// // Outside of `cfg(test)`, the generated test does not exist.
+/// test_AddNodeEvent_type_signature();
+/// ```
+///
+/// When a type drifted on purpose, setting the environment variable `ZBUS_LOCKSTEP_UPDATE` to
+/// any value makes the generated test print the type's current signature next to the expected
+/// one to standard error, instead of failing, so the new signature can be copied into the XML
+/// or the `signature` argument. Run the tests with `--nocapture` to see the output.
+///
+/// ```text
+/// ZBUS_LOCKSTEP_UPDATE=1 cargo test -- --nocapture
+/// ```
+///
+/// Its earlier name, `LOCKSTEP_UPDATE`, is read if it is not set.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[validate(xml: "xml", interface: "org.example.Node", signal: "RemoveNode")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn validate(args: TokenStream, input: TokenStream) -> TokenStream {
+    // Parse the macro arguments.
+    let args = parse_macro_input!(args as ValidateArgs);
+
+    // Parse the item struct or enum.
+    let item = parse_macro_input!(input as Item);
+    let (item_ident, item_attrs) = match &item {
+        Item::Struct(item_struct) => (item_struct.ident.clone(), &item_struct.attrs),
+        Item::Enum(item_enum) => (item_enum.ident.clone(), &item_enum.attrs),
+        _ => {
+            return syn::Error::new_spanned(
+                &item,
+                "`#[validate]` can only be applied to structs and enums.",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+    let fields = field_names(&item);
+
+    // With `skip_fields`, only the remaining fields are compared.
+    let (fields, struct_signature) = match kept_fields_signature(&args, &item) {
+        Ok(Some((kept, signature))) => (Some(kept), Some(signature)),
+        Ok(None) => (fields, None),
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    match validate_type(
+        &args,
+        &item_ident,
+        fields.as_deref(),
+        struct_signature.as_ref(),
+    ) {
+        // Without `Type`, the generated code would fail on an unsatisfied trait bound instead.
+        Ok(validation_test) if !validation_test.is_empty() && !derives_type(item_attrs) => {
+            let error = syn::Error::new(
+                item_ident.span(),
+                format!(
+                    "`{item_ident}` does not derive `Type`, which `#[validate]` needs for its \
+                     signature. Add `#[derive(zbus::zvariant::Type)]`."
+                ),
+            )
+            .to_compile_error();
+
+            quote! {
+                #item
+
+                #error
+            }
+            .into()
+        }
+        Ok(validation_test) => quote! {
+            #item
+
+            #validation_test
+        }
+        .into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// The validation of the type `item_ident` against XML, as configured by `args`: the
+/// validation test or function, and the serde assertion if requested.
+///
+/// `fields` names the fields of the type in order, if known. `struct_signature` is an
+/// expression for the signature to compare, if not that of the whole type.
+fn validate_type(
+    args: &ValidateArgs,
+    item_ident: &Ident,
+    fields: Option<&[String]>,
+    struct_signature: Option<&proc_macro2::TokenStream>,
+) -> Result<proc_macro2::TokenStream> {
+    let item_name = item_ident.to_string();
+
+    let item_struct_name = Ident::new(&item_name, proc_macro2::Span::call_site());
+    let validation_name = |default: String| {
+        let name = args.test_name.clone().unwrap_or(default);
+        Ident::new(&name, proc_macro2::Span::call_site())
+    };
+    let test_name = validation_name(match args.mode {
+        ValidateMode::Test => format!("test_{item_name}_type_signature"),
+        ValidateMode::Fn => format!("__validate_{item_name}"),
+    });
+
+    // Zero-cost check that the type implements the serde traits, if requested.
+    let serde_assertion = if args.require_serde {
+        quote! {
+            const _: () = {
+                fn assert_impl_serde<'de, T: ::serde::Serialize + ::serde::Deserialize<'de>>() {}
+                fn assert_serde() {
+                    assert_impl_serde::<#item_struct_name>();
+                }
+            };
+        }
+    } else {
+        quote! {}
+    };
+
+    // Compare against the literal signature, if provided, and skip XML altogether.
+    if let Some(signature) = &args.signature {
+        let item_signature = match struct_signature {
+            Some(struct_signature) => quote!(&#struct_signature),
+            None => quote!(<#item_struct_name as ::zbus_lockstep::zvariant::Type>::SIGNATURE),
+        };
+        let mismatch_detail = mismatch_detail(
+            quote!(mismatch.first_diff_index),
+            fields.unwrap_or_default(),
+        );
+        let update_var = update_var();
+        let body = quote! {
+            {
+                let expected_signature: ::zbus_lockstep::zvariant::Signature = #signature
+                    .parse()
+                    .map_err(|e| format!("Invalid signature: {e}"))?;
+                let item_signature = #item_signature;
+
+                if let Err(mismatch) =
+                    ::zbus_lockstep::compare_signatures(&expected_signature, item_signature)
+                {
+                    // With `ZBUS_LOCKSTEP_UPDATE` set, drift is reported rather than failing the
+                    // test.
+                    if let Some(update_var) = #update_var {
+                        ::std::eprintln!(
+                            "{update_var}: `{}` drifted from its signature:\n    \
+                             struct:    \"{}\"\n    signature: \"{}\"",
+                            stringify!(#item_struct_name),
+                            item_signature,
+                            expected_signature
+                        );
+                    } else {
+                        return ::std::result::Result::Err(format!(
+                            "Type drifted from signature: `{}` has signature \"{}\", expected \"{}\".{}",
+                            stringify!(#item_struct_name),
+                            mismatch.actual,
+                            mismatch.expected,
+                            #mismatch_detail
+                        ));
+                    }
+                }
+            }
+        };
+        let validation_test = validation_item(
+            args.mode,
+            args.test_attr.as_ref(),
+            args.ignore,
+            &test_name,
+            &body,
+        );
+
+        return Ok(quote! {
+            #serde_assertion
+
+            #validation_test
+        });
+    }
+
+    // Without `xml`, resolve the default location. Inline XML needs no location.
+    let xml_strs: Vec<Option<&str>> = if args.xml_str.is_some() || args.live.is_some() {
+        Vec::new()
+    } else if args.xml.is_empty() {
+        vec![None]
+    } else {
+        args.xml.iter().map(|p| p.to_str()).collect()
+    };
+
+    // Collect the files of all directories into one set, so that disambiguation spans them.
+    let mut xml_files = XmlFiles::new();
+    if let Some(xml) = &args.xml_str {
+        xml_files.insert(PathBuf::from("xml_str"), xml.clone());
+    }
+    for xml_str in xml_strs {
+        // `ZBUS_LOCKSTEP_XML_PATH` may list several directories, a pattern is resolved below
+        // each.
+        let xml_dirs = match zbus_lockstep::resolve_xml_paths(xml_str) {
+            Ok(xml_dirs) => xml_dirs,
+            Err(e) => {
+                return Err(syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!("Failed to resolve XML path: {e}"),
+                ));
+            }
+        };
+
+        for xml in xml_dirs {
+            xml_files.extend(collect_xml_files(&xml, args.file_filter.as_deref())?);
+        }
+    }
+
+    if args.trim_types {
+        for xml in xml_files.values_mut() {
+            *xml = zbus_lockstep::trim_type_attributes(xml);
+        }
+    }
+
+    #[cfg(feature = "dtd")]
+    if args.validate_dtd {
+        for (path_key, xml_string) in &xml_files {
+            if let Err(e) = zbus_lockstep::validate_introspection_dtd(xml_string) {
+                return Err(syn::Error::new(
+                    proc_macro2::Span::call_site(),
+                    format!(
+                        "XML file \"{}\" does not conform to the introspection DTD: {e}",
+                        path_key.display()
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Without `member_type`, `member` names whichever kind of member XML declares.
+    let inferred_args;
+    let args = match &args.signal {
+        Some(member_name) if args.infer_member_type && args.live.is_none() => {
+            inferred_args = ValidateArgs {
+                member_type: infer_member_type(&xml_files, member_name, args)?,
+                ..args.clone()
+            };
+            &inferred_args
+        }
+        _ => args,
+    };
+    if args.infer_member_type {
+        args.check_member_type(proc_macro2::Span::call_site())?;
+    }
+
+    // A running service is introspected when the test runs, for the names given.
+    let ResolvedMember {
+        interface_name,
+        signal_name,
+        xml_file_path,
+        candidates,
+    } = match &args.live {
+        Some(_) => ResolvedMember {
+            interface_name: args
+                .interface
+                .clone()
+                .expect("live target should have an interface"),
+            signal_name: args
+                .signal
+                .clone()
+                .expect("live target should have a member name"),
+            xml_file_path: PathBuf::new(),
+            candidates: Vec::new(),
+        },
+        None => match resolve_signal(&xml_files, &item_name, args)? {
+            Some(resolved) => resolved,
+            // Skipped, nothing to validate.
+            None => return Ok(quote! {}),
+        },
+    };
+
+    // Record the member on the type, if requested.
+    let member_consts = if args.emit_const {
+        let interface = match &args.interface_const {
+            Some(interface_const) => quote!(#interface_const),
+            None => quote!(#interface_name),
+        };
+
+        quote! {
+            impl #item_struct_name {
+                /// The `DBus` interface the type is validated against.
+                pub const DBUS_INTERFACE: &str = #interface;
+                /// The member of `DBUS_INTERFACE` the type is validated against.
+                pub const DBUS_MEMBER: &str = #signal_name;
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // With `all_matches`, every matching member is checked in turn.
+    let members = if args.all_matches {
+        let mut candidates = candidates;
+        candidates.sort();
+        candidates
+            .into_iter()
+            .map(
+                |(interface_name, xml_file_path, signal_name)| ResolvedMember {
+                    interface_name,
+                    signal_name,
+                    xml_file_path,
+                    candidates: Vec::new(),
+                },
+            )
+            .collect()
+    } else {
+        vec![ResolvedMember {
+            interface_name,
+            signal_name,
+            xml_file_path,
+            candidates,
+        }]
+    };
+    // The member's files, and those `interface_const` may choose from.
+    let tracked_files = track_files(members.iter().flat_map(|member| {
+        std::iter::once(&member.xml_file_path)
+            .chain(member.candidates.iter().map(|(_, path, _)| path))
+    }));
+
+    // Claim each member, so that another claim of it fails to compile.
+    let claims = if args.exclusive {
+        members
+            .iter()
+            .map(|member| exclusive_claim(item_ident, &member.interface_name, &member.signal_name))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let checks = members
+        .into_iter()
+        .map(|member| {
+            member_check_body(
+                args,
+                &item_struct_name,
+                fields,
+                struct_signature,
+                &xml_files,
+                member,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let body = quote! {
+        {
+            #(#checks)*
+        }
+    };
+    let validation_test = validation_item(
+        args.mode,
+        args.test_attr.as_ref(),
+        args.ignore,
+        &test_name,
+        &body,
+    );
+
+    Ok(quote! {
+        #serde_assertion
+
+        #member_consts
+
+        #(#claims)*
+
+        #tracked_files
+
+        #validation_test
+    })
+}
+
+/// An item claiming the member `member` of `interface` for the type `item_ident`.
+///
+/// The claim is an exported macro, named after the interface and member, which places it at
+/// the crate root wherever the type is declared. A second claim of the same member anywhere in
+/// the crate is then a duplicate definition, E0428, pointing at both types.
+fn exclusive_claim(item_ident: &Ident, interface: &str, member: &str) -> proc_macro2::TokenStream {
+    let name = format!("__lockstep_claim_{}__{member}", interface.replace('.', "_"));
+    let name = Ident::new(&name, item_ident.span());
+    let claimant = format!("Claims \"{member}\" of interface \"{interface}\" for `{item_ident}`.");
+
+    // Spanned at the type, so the error points at both claimants.
+    quote::quote_spanned! {item_ident.span()=>
+        #[doc = #claimant]
+        #[doc(hidden)]
+        #[macro_export]
+        #[allow(non_local_definitions)]
+        macro_rules! #name {
+            () => {};
+        }
+    }
+}
+
+/// The statements validating the type `item_struct_name` against the member `member`, as
+/// configured by `args`.
+fn member_check_body(
+    args: &ValidateArgs,
+    item_struct_name: &Ident,
+    fields: Option<&[String]>,
+    struct_signature: Option<&proc_macro2::TokenStream>,
+    xml_files: &XmlFiles,
+    member: ResolvedMember,
+) -> Result<proc_macro2::TokenStream> {
+    let ResolvedMember {
+        interface_name,
+        signal_name,
+        xml_file_path,
+        candidates,
+    } = member;
+
+    let xml_file_path = xml_file_path
+        .to_str()
+        .expect("XML file path should be valid UTF-8");
+
+    let member_type = args.member_type.to_string();
+
+    let undeclared_member = quote! {
+        return ::std::result::Result::Err(format!(
+            "Interface \"{interface_name}\" does not declare {} \"{}\".",
+            #member_type,
+            #signal_name
+        ))
+    };
+
+    // With `interface_const`, the interface and its file are looked up when the test runs.
+    // Inline XML is embedded as is.
+    let resolve_definition = match (&args.interface_const, &args.xml_str) {
+        _ if args.live.is_some() => {
+            let LiveTarget { bus, service, path } =
+                args.live.as_ref().expect("live target should be present");
+            let bus = match bus {
+                LiveBus::Session => quote!(::zbus_lockstep::Bus::Session),
+                LiveBus::System => quote!(::zbus_lockstep::Bus::System),
+            };
+
+            quote! {
+                let interface_name: &str = #interface_name;
+                let xml = ::zbus_lockstep::introspect(#bus, #service, #path)
+                    .map_err(|e| format!("Live introspection failed: {e}"))?;
+            }
+        }
+        (Some(interface_const), Some(xml)) => {
+            let candidate_interfaces = candidates.iter().map(|(iface, _, _)| iface);
+
+            quote! {
+                let interface_name: &str = #interface_const;
+                let candidates: &[&str] = &[#(#candidate_interfaces),*];
+                if !candidates.contains(&interface_name) {
+                    #undeclared_member;
+                }
+                let xml = String::from(#xml);
+            }
+        }
+        (Some(interface_const), None) => {
+            let candidate_interfaces = candidates.iter().map(|(iface, _, _)| iface);
+            let candidate_files = candidates
+                .iter()
+                .map(|(_, path, _)| path.to_str().expect("XML file path should be valid UTF-8"));
+            let read_xml = read_xml_file();
+
+            quote! {
+                let interface_name: &str = #interface_const;
+                let candidates: &[(&str, &str)] = &[#((#candidate_interfaces, #candidate_files)),*];
+                let Some(xml_file_path) = candidates
+                    .iter()
+                    .find(|(iface, _)| *iface == interface_name)
+                    .map(|(_, path)| *path)
+                else {
+                    #undeclared_member;
+                };
+                #read_xml
+            }
+        }
+        (None, Some(xml)) => quote! {
+            let interface_name: &str = #interface_name;
+            let xml = String::from(#xml);
+        },
+        (None, None) => {
+            let read_xml = read_xml_file();
+
+            quote! {
+                let interface_name: &str = #interface_name;
+                let xml_file_path: &str = #xml_file_path;
+                #read_xml
+            }
+        }
+    };
+
+    // Strip whitespace from `type` attributes, if requested.
+    let trim_types = if args.trim_types {
+        quote! {
+            let xml = ::zbus_lockstep::trim_type_attributes(&xml);
+        }
+    } else {
+        quote! {}
+    };
+
+    // Fail the test on unnamed signal arguments, if requested.
+    let require_arg_names = if args.require_arg_names {
+        quote! {
+            let unnamed_args = ::zbus_lockstep::get_signal_unnamed_args(
+                xml.as_bytes(),
+                interface_name,
+                #signal_name,
+            ).map_err(|e| format!("Failed to get signal arguments from XML file: {e}"))?;
+            if !unnamed_args.is_empty() {
+                return ::std::result::Result::Err(format!(
+                    "Signal \"{}\" has unnamed arguments at positions: {:?}",
+                    #signal_name,
+                    unnamed_args
+                ));
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Look up the body type of the member, and the function that looks up its argument types.
+    // Properties have no arguments.
+    let (body_type, arg_types_fn) = match args.member_type {
+        MemberType::Signal => (
+            quote! { ::zbus_lockstep::get_signal_body_type(xml.as_bytes(), interface_name, #signal_name, None) },
+            quote! { ::zbus_lockstep::get_signal_arg_types },
+        ),
+        MemberType::Method if args.direction == ArgDirection::Out => (
+            quote! { ::zbus_lockstep::get_method_return_type(xml.as_bytes(), interface_name, #signal_name, None) },
+            quote! { ::zbus_lockstep::get_method_out_arg_types },
+        ),
+        MemberType::Method => (
+            quote! { ::zbus_lockstep::get_method_args_type(xml.as_bytes(), interface_name, #signal_name, None) },
+            quote! { ::zbus_lockstep::get_method_in_arg_types },
+        ),
+        MemberType::Property => (
+            quote! { ::zbus_lockstep::get_property_type(xml.as_bytes(), interface_name, #signal_name) },
+            quote! {},
+        ),
+    };
+
+    // Compare to a single argument, if requested.
+    let body_type = match &args.arg {
+        Some(arg) => {
+            let xml_file_path = Path::new(xml_file_path);
+            let index = resolve_arg(
+                xml_file_path,
+                &xml_files[xml_file_path],
+                args.member_type,
+                args.direction,
+                &interface_name,
+                &signal_name,
+                arg,
+            )?;
+
+            quote! {
+                #arg_types_fn(xml.as_bytes(), interface_name, #signal_name).and_then(|arg_types| {
+                    arg_types.get(#index).cloned().ok_or_else(|| {
+                        ::std::format!(
+                            "Argument {} of \"{}\" not found, it has {} argument(s).",
+                            #index,
+                            #signal_name,
+                            arg_types.len()
+                        )
+                        .into()
+                    })
+                })
+            }
+        }
+        None => body_type,
+    };
+
+    // Tell flat arguments from a single struct argument, if requested.
+    let strict_wrapping = if args.strict_wrapping {
+        // Only the fields that are not skipped are compared.
+        let item_signature = match struct_signature {
+            Some(struct_signature) => quote!(&#struct_signature),
+            None => quote!(<#item_struct_name as ::zbus_lockstep::zvariant::Type>::SIGNATURE),
+        };
+        quote! {
+            let arg_types = #arg_types_fn(
+                xml.as_bytes(),
+                interface_name,
+                #signal_name,
+            )
+            .map_err(|e| format!("Failed to get argument types from XML file: {e}"))?;
+            let item_signature = #item_signature;
+            if matches!(item_signature, ::zbus_lockstep::zvariant::Signature::Structure(_))
+                && arg_types.len() != 1
+            {
+                return ::std::result::Result::Err(format!(
+                    "Member \"{}\" has {} flat arguments, but `{}` has the struct signature \"{}\". \
+                     With `strict_wrapping`, XML must declare a single struct argument.",
+                    #signal_name,
+                    arg_types.len(),
+                    stringify!(#item_struct_name),
+                    item_signature,
+                ));
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let prepare_xml = quote! {
+        #trim_types
+        #require_arg_names
+        #strict_wrapping
+    };
+
+    // Take the body signature from a signal annotation, if present.
+    let signature_annotation = match &args.signal_signature_annotation {
+        Some(annotation) => quote! {
+            let annotation = ::zbus_lockstep::get_signal_annotation(
+                xml.as_bytes(),
+                interface_name,
+                #signal_name,
+                #annotation,
+            )
+            .map_err(|e| format!("Failed to get signal annotation from XML file: {e}"))?;
+            let item_signature_from_xml = match annotation {
+                Some(value) => <::zbus_lockstep::zvariant::Signature as ::std::str::FromStr>::from_str(&value)
+                    .map_err(|e| {
+                        format!("Annotation \"{}\" holds invalid signature \"{value}\": {e}", #annotation)
+                    })?,
+                None => item_signature_from_xml,
+            };
+        },
+        None => quote! {},
+    };
+
+    // Check the XML against its snapshot, before any relaxation of the comparison.
+    let double_check = if args.double_check {
+        quote! {
+            let snapshot_path = ::std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("lockstep_snapshots")
+                .join(format!("{interface_name}.{}.signature", #signal_name));
+            if let Err(e) =
+                ::zbus_lockstep::check_signature_snapshot(&snapshot_path, &item_signature_from_xml)
+            {
+                return ::std::result::Result::Err(format!(
+                    "XML drifted from snapshot: {} \"{}\" of interface \"{interface_name}\": {e}",
+                    #member_type,
+                    #signal_name
+                ));
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // Drop a trailing `a{sv}` argument the struct omits, if allowed.
+    let allow_trailing_metadata = if args.allow_trailing_metadata {
+        quote! {
+            let arg_types = #arg_types_fn(
+                xml.as_bytes(),
+                interface_name,
+                #signal_name,
+            )
+            .map_err(|e| format!("Failed to get argument types from XML file: {e}"))?;
+            let item_signature_from_xml =
+                ::zbus_lockstep::strip_trailing_metadata(&arg_types, item_signature_from_struct);
+        }
+    } else {
+        quote! {}
+    };
+
+    // Relax the comparison as allowed, with all relaxations applied together.
+    let relaxed_comparison =
+        if args.allow_value_fields || args.any_field_order || args.allow_extra_trailing {
+            let allow_value_fields = args.allow_value_fields;
+            let any_field_order = args.any_field_order;
+            let allow_extra_trailing_fields = args.allow_extra_trailing;
+            quote! {
+                let options = ::zbus_lockstep::CompareOptions {
+                    allow_value_fields: #allow_value_fields,
+                    any_field_order: #any_field_order,
+                    allow_extra_trailing_fields: #allow_extra_trailing_fields,
+                    ..Default::default()
+                };
+                let item_signature_from_xml = if ::zbus_lockstep::signatures_are_eq_with(
+                    &item_signature_from_xml,
+                    item_signature_from_struct,
+                    options,
+                ) {
+                    item_signature_from_struct.clone()
+                } else {
+                    item_signature_from_xml
+                };
+            }
+        } else {
+            quote! {}
+        };
+
+    // Print both signatures as found, before any adjustment, if requested.
+    let print = if args.print {
+        quote! {
+            eprintln!(
+                "\"{}\" of interface \"{interface_name}\" has signature \"{}\", `{}` has signature \"{}\".",
+                #signal_name,
+                item_signature_from_xml,
+                stringify!(#item_struct_name),
+                item_signature_from_struct,
+            );
+        }
+    } else {
+        quote! {}
+    };
+
+    // Compare only the fields that are not skipped, if any are.
+    let kept_fields = match struct_signature {
+        Some(struct_signature) => quote! {
+            let item_signature_from_struct = &#struct_signature;
+        },
+        None => quote! {},
+    };
+
+    let adjust_signature = quote! {
+        #kept_fields
+        #print
+        #signature_annotation
+        #double_check
+        #allow_trailing_metadata
+        #relaxed_comparison
+    };
+
+    Ok(signature_check_body(
+        &item_struct_name,
+        fields,
+        &member_type,
+        &resolve_definition,
+        &prepare_xml,
+        &adjust_signature,
+        &body_type,
+        &signal_name,
+    ))
+}
+
+/// Generate a validation test for each of the listed types.
+///
+/// Instead of decorating each type with `#[validate]`, a whole list of types can be validated
+/// at once. The arguments of `#[validate]` come first and apply to every type, followed by
+/// the types themselves:
+///
+/// ```ignore
+/// validate_all! {
+///     xml: "xml",
+///     RemoveNodeSignal,
+///     AddNodeSignal,
+/// }
+/// ```
+///
+/// The types are matched to signals as with `#[validate]`, and each test is named after its
+/// type, e.g. `test_RemoveNodeSignal_type_signature`. Hence `test_name` cannot be used. The
+/// types are resolved from where the macro is called.
+#[proc_macro]
+pub fn validate_all(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ValidateAllInput);
+
+    if input.args.test_name.is_some() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`test_name` cannot be used with `validate_all!`, tests are named after their types.",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if !input.args.skip_fields.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "`skip_fields` cannot be used with `validate_all!`, the fields of its types are \
+             not known.",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let mut tests = proc_macro2::TokenStream::new();
+    for ty in &input.types {
+        match validate_type(&input.args, ty, None, None) {
+            Ok(validation_test) => tests.extend(validation_test),
+            Err(e) => return e.to_compile_error().into(),
+        }
+    }
+
+    tests.into()
+}
+
+/// Generate validation tests for the types listed in a manifest file.
+///
+/// Instead of decorating each type with `#[validate]`, all validation wiring can be kept in a
+/// single TOML file. The manifest maps type paths to the interface and signal they should be
+/// validated against:
+///
+/// ```toml
+/// # Optional, relative to the manifest. Defaults to the same locations as `#[validate]`.
+/// xml = "../xml"
+///
+/// ["crate::events::RemoveNodeEvent"]
+/// interface = "org.example.Node"
+/// signal = "RemoveNode"
+/// ```
+///
+/// The manifest path is relative to the crate root. One test is generated per entry, named
+/// after the type like the tests generated by `#[validate]`. The type paths are resolved
+/// from where the macro is called.
+///
+/// ## Aggregated test
+///
+/// With many types, the many small tests add to the size of the test binary and to the time
+/// `cargo test` takes to start. Setting `aggregate = true` at the top of the manifest generates
+/// a single test, `lockstep_manifest_signatures`, instead. It runs the checks of every entry's
+/// test and reports all mismatches together, rather than stopping at the first.
+///
+/// ```toml
+/// aggregate = true
+/// ```
+///
+/// The tradeoff is granularity: a single entry can no longer be run or filtered on its own,
+/// and a failure is reported for the test as a whole.
+///
+/// Types decorated with `#[validate(mode: "fn")]` can join the same test: `validations` lists
+/// the paths of their functions, named `__validate_<struct>` unless `test_name` names them,
+/// resolved from where the macro is called like the type paths.
+///
+/// ```toml
+/// aggregate = true
+/// validations = ["crate::events::__validate_AddNodeEvent"]
+/// ```
+///
+/// Without a manifest, `zbus_lockstep::aggregate_validations!` gives a single test for the
+/// functions listed there.
+///
+/// # Examples
+///
+/// ```ignore
+/// validate_from_manifest!("lockstep_manifest.toml");
+/// ```
+#[proc_macro]
+pub fn validate_from_manifest(input: TokenStream) -> TokenStream {
+    let manifest = parse_macro_input!(input as LitStr);
+
+    match manifest_validation_tests(&manifest) {
+        Ok(tests) => tests.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn manifest_validation_tests(manifest: &LitStr) -> Result<proc_macro2::TokenStream> {
+    let manifest_error = |msg: String| {
+        syn::Error::new(
+            manifest.span(),
+            format!("Invalid manifest \"{}\": {msg}", manifest.value()),
+        )
+    };
+
+    let crate_dir = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR")
+            .expect("the CARGO_MANIFEST_DIR environment variable should be set"),
+    );
+    let manifest_path = crate_dir.join(manifest.value());
+
+    let contents = std::fs::read_to_string(&manifest_path)
+        .map_err(|e| manifest_error(format!("Failed to read file: {e}")))?;
+    let table = contents
+        .parse::<toml::Table>()
+        .map_err(|e| manifest_error(format!("Failed to parse TOML: {e}")))?;
+
+    // An `xml` key is relative to the manifest itself.
+    let xml = match table.get("xml") {
+        Some(toml::Value::String(xml)) => Some(
+            manifest_path
+                .parent()
+                .expect("manifest path should have a parent directory")
+                .join(xml),
+        ),
+        Some(_) => return Err(manifest_error("`xml` should be a string.".to_string())),
+        None => None,
+    };
+
+    let aggregate = match table.get("aggregate") {
+        Some(toml::Value::Boolean(aggregate)) => *aggregate,
+        Some(_) => {
+            return Err(manifest_error(
+                "`aggregate` should be a boolean.".to_string(),
+            ))
+        }
+        None => false,
+    };
+
+    // Functions generated by `#[validate(mode: "fn")]`, run along with the entries.
+    let validations = match table.get("validations") {
+        Some(toml::Value::Array(validations)) => validations
+            .iter()
+            .map(|validation| {
+                let path = validation.as_str().ok_or_else(|| {
+                    manifest_error("`validations` should be an array of strings.".to_string())
+                })?;
+                syn::parse_str::<syn::Path>(path)
+                    .map(|fn_path| (path, fn_path))
+                    .map_err(|_| manifest_error(format!("\"{path}\" is not a valid path.")))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        Some(_) => {
+            return Err(manifest_error(
+                "`validations` should be an array of strings.".to_string(),
+            ))
+        }
+        None => Vec::new(),
+    };
+    if !validations.is_empty() && !aggregate {
+        return Err(manifest_error(
+            "`validations` needs `aggregate = true`, each function is run by the aggregated \
+             test."
+                .to_string(),
+        ));
+    }
+
+    let xml = zbus_lockstep::resolve_xml_path(xml.as_ref().and_then(|p| p.to_str()))
+        .map_err(|e| manifest_error(format!("Failed to resolve XML path: {e}")))?;
+    let xml_files = collect_xml_files(&xml, None)?;
+
+    let mut tests = proc_macro2::TokenStream::new();
+    let mut checks = Vec::new();
+    let mut tracked = Vec::new();
+
+    for (type_path, entry) in table
+        .iter()
+        .filter(|(key, _)| !matches!(key.as_str(), "xml" | "aggregate" | "validations"))
+    {
+        let ty = syn::parse_str::<syn::Path>(type_path)
+            .map_err(|_| manifest_error(format!("\"{type_path}\" is not a valid type path.")))?;
+
+        let field = |key: &str| {
+            entry
+                .get(key)
+                .and_then(toml::Value::as_str)
+                .ok_or_else(|| manifest_error(format!("\"{type_path}\" is missing `{key}`.")))
+        };
+        let interface_name = field("interface")?;
+        let signal_name = field("signal")?;
+
+        // Find the file that declares this interface and signal.
+        let xml_file_path =
+            find_member_file(&xml_files, interface_name, MemberType::Signal, signal_name)
+                .map_err(|e| manifest_error(e.to_string()))?;
+
+        let xml_file_path = xml_file_path.ok_or_else(|| {
+            manifest_error(format!(
+                "Signal \"{signal_name}\" of interface \"{interface_name}\" not found."
+            ))
+        })?;
+        tracked.push(xml_file_path.clone());
+        let xml_file_path = xml_file_path
+            .to_str()
+            .expect("XML file path should be valid UTF-8");
+
+        let type_name = &ty
+            .segments
+            .last()
+            .expect("type path should have at least one segment")
+            .ident;
+        let test_name = Ident::new(
+            &format!("test_{type_name}_type_signature"),
+            proc_macro2::Span::call_site(),
+        );
+
+        let read_xml = read_xml_file();
+        let body = signature_check_body(
+            &ty,
+            None,
+            "signal",
+            &quote! {
+                let interface_name: &str = #interface_name;
+                let xml_file_path: &str = #xml_file_path;
+                #read_xml
+            },
+            &quote! {},
+            &quote! {},
+            &quote! {
+                ::zbus_lockstep::get_signal_body_type(
+                    xml.as_bytes(),
+                    interface_name,
+                    #signal_name,
+                    None,
+                )
+            },
+            signal_name,
+        );
+
+        if aggregate {
+            // The same checks as the test of the entry, returning the mismatch.
+            let check = check_closure(&body);
+            checks.push(quote! {
+                (#type_path, (#check) as ::zbus_lockstep::Validation)
+            });
+        } else {
+            tests.extend(validation_item(
+                ValidateMode::Test,
+                None,
+                false,
+                &test_name,
+                &body,
+            ));
+        }
+    }
+
+    checks.extend(validations.iter().map(|(path, fn_path)| {
+        quote! {
+            (#path, #fn_path as ::zbus_lockstep::Validation)
+        }
+    }));
+
+    if aggregate {
+        tests.extend(quote! {
+            #[cfg(test)]
+            #[test]
+            fn lockstep_manifest_signatures() {
+                ::zbus_lockstep::assert_validations(&[#(#checks),*]);
+            }
+        });
+    }
+
+    tests.extend(track_files(&tracked));
+
+    Ok(tests)
+}
+
+/// Validate fields against the D-Bus values they hold.
+///
+/// Where `#[validate]` compares a whole type to one member, the `Lockstep` derive compares
+/// individual fields, for structs that aggregate several independent values. Each field marked
+/// with `#[lockstep(...)]` is compared to a property, or to one argument of a signal:
+///
+/// ```ignore
+/// #[derive(Lockstep)]
+/// #[lockstep(xml = "xml")]
+/// struct PlayerStatus {
+///     #[lockstep(interface = "org.example.Player", property = "Volume")]
+///     volume: f64,
+///     #[lockstep(interface = "org.example.Player", signal = "Seeked", arg = "position")]
+///     position: i64,
+/// }
+/// ```
+///
+/// The optional `xml` of the struct's `#[lockstep(...)]` is the path of the XML files, which
+/// defaults to the same locations as `#[validate]`. Each field needs an `interface` and
+/// either a `property` or a `signal` with the name of the `arg`. Fields without the attribute
+/// are not validated.
+///
+/// One test is generated per marked field, named after the struct and the field, e.g.
+/// `test_PlayerStatus_volume_type_signature`.
+#[proc_macro_derive(Lockstep, attributes(lockstep))]
+pub fn derive_lockstep(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+
+    match field_validation_tests(&input) {
+        Ok(tests) => tests.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// A field marked with `#[lockstep(...)]` and the member it holds.
+struct LockstepField {
+    interface: String,
+    member_type: MemberType,
+    member: String,
+    // The argument of a signal
+    arg: Option<String>,
+}
+
+impl LockstepField {
+    fn parse(attr: &syn::Attribute) -> Result<Self> {
+        let mut interface = None;
+        let mut property = None;
+        let mut signal = None;
+        let mut arg = None;
+
+        attr.parse_nested_meta(|meta| {
+            let slot = if meta.path.is_ident("interface") {
+                &mut interface
+            } else if meta.path.is_ident("property") {
+                &mut property
+            } else if meta.path.is_ident("signal") {
+                &mut signal
+            } else if meta.path.is_ident("arg") {
+                &mut arg
+            } else {
+                return Err(meta.error("expected `interface`, `property`, `signal` or `arg`."));
+            };
+            *slot = Some(meta.value()?.parse::<LitStr>()?.value());
+            Ok(())
+        })?;
+
+        let interface = interface
+            .ok_or_else(|| syn::Error::new_spanned(attr, "`#[lockstep]` needs an `interface`."))?;
+
+        match (property, signal, arg) {
+            (Some(property), None, None) => Ok(LockstepField {
+                interface,
+                member_type: MemberType::Property,
+                member: property,
+                arg: None,
+            }),
+            (None, Some(signal), Some(arg)) => Ok(LockstepField {
+                interface,
+                member_type: MemberType::Signal,
+                member: signal,
+                arg: Some(arg),
+            }),
+            _ => Err(syn::Error::new_spanned(
+                attr,
+                "`#[lockstep]` needs either a `property`, or a `signal` and its `arg`.",
+            )),
+        }
+    }
+}
+
+fn field_validation_tests(input: &syn::DeriveInput) -> Result<proc_macro2::TokenStream> {
+    let syn::Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "`Lockstep` can only be derived for structs.",
+        ));
+    };
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "`Lockstep` cannot be derived for generic structs.",
+        ));
+    }
+
+    let mut xml = None;
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("lockstep"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("xml") {
+                xml = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("expected `xml`."))
+            }
+        })?;
+    }
+
+    let xml = zbus_lockstep::resolve_xml_path(xml.as_deref()).map_err(|e| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Failed to resolve XML path: {e}"),
+        )
+    })?;
+    let xml_files = collect_xml_files(&xml, None)?;
+
+    let mut tests = proc_macro2::TokenStream::new();
+    let mut tracked = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let Some(attr) = field
+            .attrs
+            .iter()
+            .find(|attr| attr.path().is_ident("lockstep"))
+        else {
+            continue;
+        };
+        let LockstepField {
+            interface,
+            member_type,
+            member,
+            arg,
+        } = LockstepField::parse(attr)?;
+
+        let not_found = |what: String| {
+            syn::Error::new_spanned(
+                attr,
+                format!("{what} of interface \"{interface}\" not found."),
+            )
+        };
+
+        // A conflict is reported at the field, as a missing member is.
+        let xml_file_path = find_member_file(&xml_files, &interface, member_type, &member)
+            .map_err(|e| syn::Error::new_spanned(attr, e))?
+            .ok_or_else(|| {
+                let kind = match member_type {
+                    MemberType::Property => "Property",
+                    _ => "Signal",
+                };
+                not_found(format!("{kind} \"{member}\""))
+            })?;
+
+        let body_type = match &arg {
+            Some(arg) => {
+                // A missing argument is better reported now than when the test runs.
+                let xml_string = &xml_files[&xml_file_path];
+                if zbus_lockstep::get_signal_body_type(
+                    xml_string.as_bytes(),
+                    &interface,
+                    &member,
+                    Some(arg),
+                )
+                .is_err()
+                {
+                    return Err(not_found(format!(
+                        "Argument \"{arg}\" of signal \"{member}\""
+                    )));
+                }
+
+                quote! {
+                    ::zbus_lockstep::get_signal_body_type(
+                        xml.as_bytes(),
+                        interface_name,
+                        #member,
+                        Some(#arg),
+                    )
+                }
+            }
+            None => quote! {
+                ::zbus_lockstep::get_property_type(xml.as_bytes(), interface_name, #member)
+            },
+        };
+
+        tracked.push(xml_file_path.clone());
+        let xml_file_path = xml_file_path
+            .to_str()
+            .expect("XML file path should be valid UTF-8");
+        let read_xml = read_xml_file();
+        let resolve_definition = quote! {
+            let interface_name: &str = #interface;
+            let xml_file_path: &str = #xml_file_path;
+            #read_xml
+        };
+
+        let field_name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        };
+        let test_name = Ident::new(
+            &format!("test_{}_{field_name}_type_signature", input.ident),
+            proc_macro2::Span::call_site(),
+        );
+
+        let body = signature_check_body(
+            &field.ty,
+            None,
+            &member_type.to_string(),
+            &resolve_definition,
+            &quote! {},
+            &quote! {},
+            &body_type,
+            &member,
+        );
+        tests.extend(validation_item(
+            ValidateMode::Test,
+            None,
+            false,
+            &test_name,
+            &body,
+        ));
+    }
+
+    tests.extend(track_files(&tracked));
+
+    Ok(tests)
+}
+
+/// The file of `xml_files` that declares the `member_type` named `member` of `interface`.
+///
+/// The files are searched in sorted path order. The member may be declared in several files, as
+/// when XML is vendored in several places, if all agree on its signature, and the first file is
+/// returned. Differing declarations are a conflict.
+fn find_member_file(
+    xml_files: &XmlFiles,
+    interface: &str,
+    member_type: MemberType,
+    member: &str,
+) -> Result<Option<PathBuf>> {
+    let mut paths: Vec<&PathBuf> = xml_files.keys().collect();
+    paths.sort();
+
+    // The first file declaring the member, with the signature it declares.
+    let mut found: Option<(&PathBuf, Option<zvariant::Signature>)> = None;
+
+    for path_key in paths {
+        let xml_string = &xml_files[path_key];
+        let node = parse_xml_file(path_key, xml_string)?;
+
+        let declares_member = zbus_lockstep::all_interfaces(&node)
+            .iter()
+            .any(|candidate| {
+                candidate.name() == interface
+                    && match member_type {
+                        MemberType::Signal => candidate
+                            .signals()
+                            .iter()
+                            .any(|signal| signal.name() == member),
+                        MemberType::Method => candidate
+                            .methods()
+                            .iter()
+                            .any(|method| method.name() == member),
+                        MemberType::Property => candidate
+                            .properties()
+                            .iter()
+                            .any(|property| property.name() == member),
+                    }
+            });
+        if !declares_member {
+            continue;
+        }
+
+        let signature =
+            member_signature(xml_string, member_type, ArgDirection::In, interface, member);
+        match &found {
+            None => found = Some((path_key, signature)),
+            Some((first_path, first_signature)) => {
+                let identical = match (first_signature, &signature) {
+                    (Some(first), Some(signature)) => {
+                        zbus_lockstep::signatures_are_eq(first, signature)
+                    }
+                    _ => false,
+                };
+
+                if !identical {
+                    return Err(syn::Error::new(
+                        proc_macro2::Span::call_site(),
+                        format!(
+                            "Conflicting declarations of {member_type} \"{member}\" of interface \
+                             \"{interface}\" in \"{}\" and \"{}\".",
+                            first_path.display(),
+                            path_key.display()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(found.map(|(path, _)| path.clone()))
+}
+
+/// Collect the XML files selected by `xml`, keyed by their file path.
+///
+/// `xml` is an XML file, a directory or a glob pattern, walked like `list_signals` walks it,
+/// see [`read_xml_files`]. If `file_filter` is provided, only files whose name matches the
+/// pattern are included.
+fn collect_xml_files(xml: &Path, file_filter: Option<&str>) -> Result<XmlFiles> {
+    Ok(read_xml_files(xml, file_filter)?.as_ref().clone())
+}
+
+/// The path given by an `xml` argument, with a leading `$OUT_DIR` replaced by the `OUT_DIR` of
+/// the crate being compiled.
+fn xml_path(lit: &LitStr) -> Result<PathBuf> {
+    let value = lit.value();
+    let Some(relative) = value
+        .strip_prefix("$OUT_DIR")
+        .filter(|relative| relative.is_empty() || relative.starts_with(['/', '\\']))
+    else {
+        return Ok(PathBuf::from(value));
+    };
+
+    let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+        syn::Error::new(
+            lit.span(),
+            format!(
+                "`xml` path \"{value}\" is relative to `$OUT_DIR`, but `OUT_DIR` is not set. \
+                 It is set for crates with a build script."
+            ),
+        )
+    })?;
+
+    Ok(PathBuf::from(out_dir).join(relative.trim_start_matches(['/', '\\'])))
+}
+
+/// Read the XML files selected by `xml` and `file_filter` with
+/// [`zbus_lockstep::read_xml_files`], keyed by their file path.
+///
+/// The result is cached by canonicalized path and filter, so later calls for the same files
+/// only list them and compare their modification times and lengths, rather than reading them.
+fn read_xml_files(xml: &Path, file_filter: Option<&str>) -> Result<Arc<XmlFiles>> {
+    let error = |e: Box<dyn std::error::Error>| {
+        syn::Error::new(proc_macro2::Span::call_site(), e.to_string())
+    };
+
+    let key = (
+        xml.canonicalize().unwrap_or_else(|_| xml.to_path_buf()),
+        file_filter.map(str::to_owned),
+    );
+
+    // Added, removed and edited files all change the stamps.
+    let stamps: FileStamps = zbus_lockstep::find_xml_files(xml, file_filter)
+        .map_err(error)?
+        .into_iter()
+        .map(|path| {
+            let metadata = std::fs::metadata(&path).ok();
+            let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+            let len = metadata.map_or(0, |m| m.len());
+            (path, modified, len)
+        })
+        .collect();
+
+    let mut cache = XML_FILES_CACHE
+        .lock()
+        .expect("XML files cache should not be poisoned");
+    if let Some((cached_stamps, xml_files)) = cache.get(&key) {
+        if *cached_stamps == stamps {
+            return Ok(Arc::clone(xml_files));
+        }
+    }
+
+    let xml_files = stamps
+        .iter()
+        .map(|(path, _, _)| {
+            zbus_lockstep::read_xml_file(path)
+                .map(|xml| (path.clone(), xml))
+                .map_err(error)
+        })
+        .collect::<Result<XmlFiles>>()?;
+    let xml_files = Arc::new(xml_files);
+    cache.insert(key, (stamps, Arc::clone(&xml_files)));
+
+    Ok(xml_files)
+}
+
+/// Parse the contents of the XML file at `path`, naming the file on failure.
+///
+/// The result is cached, so the same contents are parsed once.
+fn parse_xml_file(path: &Path, xml: &str) -> Result<Arc<zbus_xml::Node<'static>>> {
+    let mut hasher = DefaultHasher::new();
+    xml.hash(&mut hasher);
+    let key = (path.to_path_buf(), hasher.finish());
+
+    let mut cache = XML_NODE_CACHE
+        .lock()
+        .expect("XML node cache should not be poisoned");
+    if let Some(node) = cache.get(&key) {
+        return Ok(Arc::clone(node));
+    }
+
+    let node = zbus_xml::Node::from_reader(xml.as_bytes()).map_err(|e| {
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!("Failed to parse XML file: \"{}\" Err: {e}", path.display()),
+        )
+    })?;
+    let node = Arc::new(node);
+    cache.insert(key, Arc::clone(&node));
+
+    Ok(node)
+}
+
+/// Items making the crate depend on the files at `paths`, so that cargo compiles it again, and
+/// the macro expands again, when one of them is edited.
+///
+/// Each file is included with `include_bytes!`, which the compiler records as a dependency.
+/// Paths that do not name a file, such as that of inline XML, are left out.
+fn track_files(paths: impl IntoIterator<Item = impl AsRef<Path>>) -> proc_macro2::TokenStream {
+    let paths: BTreeSet<String> = paths
+        .into_iter()
+        .filter_map(|path| path.as_ref().canonicalize().ok())
+        .filter(|path| path.is_file())
+        .filter_map(|path| path.to_str().map(str::to_owned))
+        .collect();
+
+    quote! {
+        #(const _: &[u8] = include_bytes!(#paths);)*
+    }
+}
+
+/// Read the XML file at `xml_file_path`, a `&str`, into `xml` with
+/// [`zbus_lockstep::read_xml_file`], failing the check if it cannot be read.
+fn read_xml_file() -> proc_macro2::TokenStream {
+    quote! {
+        let xml = ::zbus_lockstep::read_xml_file(xml_file_path).map_err(|e| e.to_string())?;
+    }
+}
+
+/// Wrap the checks in `body` in a test, or in a function returning the mismatch, per `mode`.
+///
+/// The test is marked with `test_attr`, or `#[test]` if not provided, and `#[ignore]` if
+/// `ignore` is set.
+fn validation_item(
+    mode: ValidateMode,
+    test_attr: Option<&syn::Meta>,
+    ignore: bool,
+    name: &Ident,
+    body: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let test_attr = match test_attr {
+        Some(test_attr) => quote!(#test_attr),
+        None => quote!(test),
+    };
+    let ignore = if ignore {
+        quote!(#[ignore = "lockstep validation"])
+    } else {
+        quote! {}
+    };
+
+    let check = check_closure(body);
+    match mode {
+        ValidateMode::Test => quote! {
+            #[cfg(test)]
+            #[#test_attr]
+            #ignore
+            fn #name() {
+                if let ::std::result::Result::Err(mismatch) = (#check)() {
+                    panic!("{mismatch}");
+                }
+            }
+        },
+        ValidateMode::Fn => quote! {
+            pub fn #name() -> ::std::result::Result<(), String> {
+                (#check)()
+            }
+        },
+    }
+}
+
+/// A closure running the checks in `body`, which return the message of a failing check as an
+/// `Err`. It captures nothing, so it also coerces to a `zbus_lockstep::Validation`.
+fn check_closure(body: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        || -> ::std::result::Result<(), String> {
+            #body
+            ::std::result::Result::Ok(())
+        }
+    }
+}
+
+/// The statements comparing the signature of `ty` to the signal body type in XML, returning
+/// the mismatch as an `Err` from the enclosing check, see [`check_closure`].
+///
+/// `resolve_definition` must bind `interface_name`, a `&str`, and `xml`, the XML `String`.
+/// `prepare_xml` runs once `xml` is bound, and may rebind it.
+/// `adjust_signature` runs once both signatures are bound and may rebind
+/// `item_signature_from_xml`. `body_type` is the expression that looks up the XML body type
+/// from `xml`, such as a call to `zbus_lockstep::get_signal_body_type`. `fields` names the
+/// fields of `ty` in order, if known, to point at the first that differs. `member_type` is
+/// the kind of member, as named in messages.
+#[allow(clippy::too_many_arguments)]
+fn signature_check_body(
+    ty: &impl quote::ToTokens,
+    fields: Option<&[String]>,
+    member_type: &str,
+    resolve_definition: &proc_macro2::TokenStream,
+    prepare_xml: &proc_macro2::TokenStream,
+    adjust_signature: &proc_macro2::TokenStream,
+    body_type: &proc_macro2::TokenStream,
+    signal_name: &str,
+) -> proc_macro2::TokenStream {
+    let mismatch_detail = mismatch_detail(
+        quote!(mismatch.first_diff_index),
+        fields.unwrap_or_default(),
+    );
+
+    // A struct without fields has the signature `y` unless it is a unit struct, so whether the
+    // type is empty is decided by its fields, if known.
+    let fieldless = fields.is_some_and(|fields| fields.is_empty());
+    let update_var = update_var();
+
+    quote! {
+        {
+            #resolve_definition
+
+            #prepare_xml
+
+            let item_signature_from_xml = #body_type
+                .map_err(|e| format!("Failed to get body type from XML file: {e}"))?;
+            let item_signature_from_struct = <#ty as ::zbus_lockstep::zvariant::Type>::SIGNATURE;
+
+            #adjust_signature
+
+            // With `ZBUS_LOCKSTEP_UPDATE` set, drift is reported rather than failing the test.
+            let report_drift = || -> bool {
+                let Some(update_var) = #update_var else {
+                    return false;
+                };
+                ::std::eprintln!(
+                    "{update_var}: `{}` drifted from \"{}\" of interface \"{interface_name}\":\n    \
+                     struct: \"{}\"\n    XML:    \"{}\"",
+                    stringify!(#ty),
+                    #signal_name,
+                    item_signature_from_struct,
+                    item_signature_from_xml
+                );
+                true
+            };
+
+            // An empty body trivially matches a type without fields.
+            if item_signature_from_xml == ::zbus_lockstep::zvariant::Signature::Unit {
+                if !(#fieldless
+                    || *item_signature_from_struct == ::zbus_lockstep::zvariant::Signature::Unit
+                    || report_drift())
+                {
+                    return ::std::result::Result::Err(format!(
+                        "Type drifted from XML: `{}` has signature \"{}\", but the {} declares no \
+                         arguments: \"{}\" of interface \"{interface_name}\".",
+                        stringify!(#ty),
+                        item_signature_from_struct,
+                        #member_type,
+                        #signal_name
+                    ));
+                }
+            } else if let Err(mismatch) = ::zbus_lockstep::compare_signatures(
+                &item_signature_from_xml,
+                item_signature_from_struct,
+            ) {
+                if !report_drift() {
+                    return ::std::result::Result::Err(format!(
+                        "Type drifted from XML: `{}` does not match \"{}\" of interface \"{interface_name}\": {mismatch}{}",
+                        stringify!(#ty),
+                        #signal_name,
+                        #mismatch_detail
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// An expression evaluating to the name of the environment variable requesting drift to be
+/// reported rather than failing, if set: `ZBUS_LOCKSTEP_UPDATE`, or its earlier name,
+/// `LOCKSTEP_UPDATE`.
+fn update_var() -> proc_macro2::TokenStream {
+    quote! {
+        ["ZBUS_LOCKSTEP_UPDATE", "LOCKSTEP_UPDATE"]
+            .into_iter()
+            .find(|var| ::std::env::var_os(var).is_some())
+    }
+}
+
+/// An expression naming the field at `first_diff_index`, an `Option<usize>`, for use in an
+/// assertion message. Evaluates to an empty string if there is no index.
+fn mismatch_detail(
+    first_diff_index: proc_macro2::TokenStream,
+    fields: &[String],
+) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            let fields: &[&str] = &[#(#fields),*];
+            match #first_diff_index {
+                Some(index) => match fields.get(index) {
+                    Some(field) => format!(" First difference at field {index}, `{field}`."),
+                    None => format!(" First difference at field {index}."),
+                },
+                None => String::new(),
+            }
+        }
+    }
+}
+
+/// The signature of a member in `xml`, as the generated test will look it up, if found.
+fn member_signature(
+    xml: &str,
+    member_type: MemberType,
+    direction: ArgDirection,
+    interface_name: &str,
+    member_name: &str,
+) -> Option<zvariant::Signature> {
+    let xml = xml.as_bytes();
+    match member_type {
+        MemberType::Signal => {
+            zbus_lockstep::get_signal_body_type(xml, interface_name, member_name, None)
+        }
+        MemberType::Method if direction == ArgDirection::Out => {
+            zbus_lockstep::get_method_return_type(xml, interface_name, member_name, None)
+        }
+        MemberType::Method => {
+            zbus_lockstep::get_method_args_type(xml, interface_name, member_name, None)
+        }
+        MemberType::Property => zbus_lockstep::get_property_type(xml, interface_name, member_name),
+    }
+    .ok()
+}
+
+/// The index of `arg` among the arguments of the `member_type` named `member` of `interface`,
+/// declared in `xml`, the contents of `path`.
+///
+/// Fails, listing the arguments, if there is no such argument.
+fn resolve_arg(
+    path: &Path,
+    xml: &str,
+    member_type: MemberType,
+    direction: ArgDirection,
+    interface: &str,
+    member: &str,
+    arg: &ArgSelector,
+) -> Result<usize> {
+    let node = parse_xml_file(path, xml)?;
+    let interfaces = zbus_lockstep::all_interfaces(&node);
+    let candidate = interfaces
+        .iter()
+        .find(|candidate| candidate.name() == interface);
+
+    // The names of the arguments of the member, in order.
+    let names: Vec<Option<String>> = match (candidate, member_type) {
+        (Some(candidate), MemberType::Signal) => candidate
+            .signals()
+            .iter()
+            .filter(|signal| signal.name() == member)
+            .flat_map(|signal| signal.args())
+            .map(|arg| arg.name().map(str::to_owned))
+            .collect(),
+        (Some(candidate), MemberType::Method) => candidate
+            .methods()
+            .iter()
+            .filter(|method| method.name() == member)
+            .flat_map(|method| method.args())
+            .filter(|arg| {
+                (arg.direction() == Some(ArgDirection::Out)) == (direction == ArgDirection::Out)
+            })
+            .map(|arg| arg.name().map(str::to_owned))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let index = match arg {
+        ArgSelector::Index(index) => Some(*index).filter(|index| *index < names.len()),
+        ArgSelector::Name(name) => names
+            .iter()
+            .position(|candidate| candidate.as_deref() == Some(name.as_str())),
+    };
+
+    index.ok_or_else(|| {
+        let available: Vec<String> = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| match name {
+                Some(name) => format!("{index} `{name}`"),
+                None => index.to_string(),
+            })
+            .collect();
+        let available = if available.is_empty() {
+            String::from("none")
+        } else {
+            available.join(", ")
+        };
+
+        syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "`arg` {arg} not found in {member_type} \"{member}\" of interface \"{interface}\". \
+                 Available arguments: {available}."
+            ),
+        )
+    })
+}
+
+/// The field names of a struct in declaration order, or their indices for a tuple struct.
+/// Enums have no fields to point at.
+fn field_names(item: &Item) -> Option<Vec<String>> {
+    match item {
+        Item::Struct(item_struct) => Some(
+            item_struct
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(index, field)| match &field.ident {
+                    Some(ident) => ident.to_string(),
+                    None => index.to_string(),
+                })
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+/// The names of the fields of `item` that `skip_fields` keeps, and an expression for the
+/// signature of a struct of only those fields.
+///
+/// Returns `None` if no fields are skipped.
+fn kept_fields_signature(
+    args: &ValidateArgs,
+    item: &Item,
+) -> Result<Option<(Vec<String>, proc_macro2::TokenStream)>> {
+    if args.skip_fields.is_empty() {
+        return Ok(None);
+    }
+
+    let Item::Struct(item_struct) = item else {
+        return Err(syn::Error::new_spanned(
+            item,
+            "`skip_fields` only applies to structs.",
+        ));
+    };
+
+    let names = field_names(item).unwrap_or_default();
+    if let Some(skipped) = args
+        .skip_fields
+        .iter()
+        .find(|skipped| !names.contains(&skipped.value()))
+    {
+        return Err(syn::Error::new(
+            skipped.span(),
+            format!(
+                "`skip_fields` names `{}`, which is not a field of `{}`.",
+                skipped.value(),
+                item_struct.ident
+            ),
+        ));
+    }
+
+    let (kept_names, kept_types): (Vec<String>, Vec<&syn::Type>) = names
+        .into_iter()
+        .zip(item_struct.fields.iter())
+        .filter(|(name, _)| {
+            !args
+                .skip_fields
+                .iter()
+                .any(|skipped| skipped.value() == *name)
+        })
+        .map(|(name, field)| (name, &field.ty))
+        .unzip();
+
+    // The kept fields form a struct, as the derived signature would without the skipped ones.
+    // The type itself is still named, as it is when all of its fields are compared.
+    let item_ident = &item_struct.ident;
+    let signature = if kept_types.is_empty() {
+        quote! {
+            {
+                let _ = <#item_ident as ::zbus_lockstep::zvariant::Type>::SIGNATURE;
+                ::zbus_lockstep::zvariant::Signature::Unit
+            }
+        }
+    } else {
+        quote! {
+            {
+                let _ = <#item_ident as ::zbus_lockstep::zvariant::Type>::SIGNATURE;
+                let fields: &[&::zbus_lockstep::zvariant::Signature] =
+                    &[#(<#kept_types as ::zbus_lockstep::zvariant::Type>::SIGNATURE),*];
+                let fields: ::std::string::String =
+                    fields.iter().map(|field| field.to_string()).collect();
+                <::zbus_lockstep::zvariant::Signature as ::std::str::FromStr>::from_str(
+                    &format!("({fields})"),
+                )
+                .expect("Signatures of the kept fields should form a valid signature.")
+            }
+        }
+    };
+
+    Ok(Some((kept_names, signature)))
+}
+
+/// Whether `attrs` derive `Type`, by any path ending in `Type`.
+///
+/// This is best-effort: a derive under `cfg_attr` is assumed to be one if it mentions `Type`,
+/// and a hand-written implementation is not seen.
+fn derives_type(attrs: &[syn::Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        if attr.path().is_ident("derive") {
+            attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Path, Token![,]>::parse_terminated,
+            )
+            .is_ok_and(|paths| {
+                paths.iter().any(|path| {
+                    path.segments
+                        .last()
+                        .is_some_and(|last| last.ident == "Type")
+                })
+            })
+        } else if attr.path().is_ident("cfg_attr") {
+            let tokens = quote::ToTokens::to_token_stream(&attr.meta).to_string();
+            tokens.contains("derive") && tokens.contains("Type")
+        } else {
+            false
+        }
+    })
+}
+
+/// The member a type is validated against, as found in XML.
+#[derive(Debug)]
+struct ResolvedMember {
+    interface_name: String,
+    signal_name: String,
+    xml_file_path: PathBuf,
+    // Every (interface, file, signal) that matches, for `interface_const` to choose from when
+    // the test runs
+    candidates: Vec<(String, PathBuf, String)>,
+}
+
+/// The kind of member named `member_name` in `xml_files`, among the interfaces `args` select.
+///
+/// Signals take precedence over methods, and methods over properties. If no member of that
+/// name is found, it is taken to be a signal, for resolution to report it missing.
+fn infer_member_type(
+    xml_files: &XmlFiles,
+    member_name: &str,
+    args: &ValidateArgs,
+) -> Result<MemberType> {
+    let mut found = Vec::new();
+
+    for (path_key, xml_string) in xml_files {
+        let node = parse_xml_file(path_key, xml_string)?;
+
+        for interface in zbus_lockstep::all_interfaces(&node) {
+            if !args.interface_matches(interface.name().as_str()) {
+                continue;
+            }
+
+            if interface.signals().iter().any(|m| m.name() == member_name) {
+                found.push(MemberType::Signal);
+            }
+            if interface.methods().iter().any(|m| m.name() == member_name) {
+                found.push(MemberType::Method);
+            }
+            if interface
+                .properties()
+                .iter()
+                .any(|m| m.name() == member_name)
+            {
+                found.push(MemberType::Property);
+            }
+        }
+    }
+
+    Ok(
+        [MemberType::Signal, MemberType::Method, MemberType::Property]
+            .into_iter()
+            .find(|member_type| found.contains(member_type))
+            .unwrap_or(MemberType::Signal),
+    )
+}
+
+/// Find the member of `xml_files` that the type `item_name` is validated against, per the
+/// `interface`, member name and matching arguments of `args`.
+///
+/// Returns `None` if no member matches and `args` skip a missing member.
+fn resolve_signal(
+    xml_files: &XmlFiles,
+    item_name: &str,
+    args: &ValidateArgs,
+) -> Result<Option<ResolvedMember>> {
+    // These are later needed to call `get_signal_body_type`.
+    let mut xml_file_path = None;
+    let mut interface_name = None;
+    let mut signal_name = None;
+
+    // The signature of the match found by name, to tell conflicting duplicates from identical
+    // ones.
+    let mut matched_signature = None;
+
+    // Whether differing matches were found by name, to be reported once the search completes.
+    let mut ambiguous = false;
+
+    // Whether the interface given by `interface` exists, to tell a missing interface from a
+    // missing member.
+    let mut interface_found = false;
+
+    // Every (interface, file, signal) that matches, to be resolved by `interface_const` or
+    // `first_match`.
+    let mut candidates: Vec<(String, PathBuf, String)> = Vec::new();
+
+    // Iterate over `xml_files` and find the signal that is contained in the struct's name.
+    // Or if `signal_arg` is provided, use that. Or if `interface` is provided and declares
+    // only one signal, use that.
+    for (path_key, xml_string) in xml_files {
+        let node = parse_xml_file(path_key, xml_string)?;
+
+        for interface in zbus_lockstep::all_interfaces(&node) {
+            // We were called with an interface argument, so if the interface name does not match,
+            // skip it.
+            if !args.interface_matches(interface.name().as_str()) {
+                continue;
+            }
+            interface_found = true;
+
+            let member_names: Vec<String> = match args.member_type {
+                MemberType::Signal => interface
+                    .signals()
+                    .iter()
+                    .map(|signal| signal.name().to_string())
+                    .collect(),
+                MemberType::Method => interface
+                    .methods()
+                    .iter()
+                    .map(|method| method.name().to_string())
+                    .collect(),
+                MemberType::Property => interface
+                    .properties()
+                    .iter()
+                    .map(|property| property.name().to_string())
+                    .collect(),
+            };
+
+            let sole_member =
+                args.interface.is_some() && args.signal.is_none() && member_names.len() == 1;
+
+            for xml_signal_name in member_names {
+                // An explicit member name takes precedence, the type's name is not considered.
+                let is_match = match &args.signal {
+                    Some(signal) => xml_signal_name == *signal,
+                    None => sole_member || args.name_match.matches(item_name, &xml_signal_name),
+                };
+
+                if is_match {
+                    let signature = member_signature(
+                        xml_string,
+                        args.member_type,
+                        args.direction,
+                        interface.name().as_str(),
+                        &xml_signal_name,
+                    );
+
+                    // If we have found a matching signal in an earlier iteration,
+                    // and the ambiguity is not to be resolved after the search:
+                    if interface_name.is_some()
+                        && signal_name.is_some()
+                        && args.interface_const.is_none()
+                        && !args.first_match
+                        && !args.all_matches
+                    {
+                        // The same signal, vendored in several places, is no ambiguity.
+                        if let (Some(signature), Some(matched_signature)) =
+                            (&signature, &matched_signature)
+                        {
+                            if zbus_lockstep::signatures_are_eq(signature, matched_signature) {
+                                continue;
+                            }
+                        }
+
+                        ambiguous = true;
+                    }
+                    interface_name = Some(interface.name().to_string());
+                    signal_name = Some(xml_signal_name.to_string());
+                    xml_file_path = Some(path_key.clone());
+                    matched_signature = signature;
+                    candidates.push((
+                        interface.name().to_string(),
+                        path_key.clone(),
+                        xml_signal_name.to_string(),
+                    ));
+                }
+            }
+        }
+    }
+
+    // Lets be nice and provide a informative compiler error message.
+
+    // We searched all XML files and did not find a match.
+    if interface_name.is_none() && args.skip_missing {
+        return Ok(None);
+    }
+    if let (None, Some(interface)) = (&interface_name, &args.interface) {
+        let message = match &args.signal {
+            _ if !interface_found => format!("Interface \"{interface}\" not found."),
+            Some(signal) => format!(
+                "No {} '{signal}' found on interface \"{interface}\".",
+                args.member_type
+            ),
+            None => format!(
+                "No {} matching name '{item_name}' found on interface \"{interface}\".",
+                args.member_type
+            ),
+        };
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), message));
+    }
+    if interface_name.is_none() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "No interface matching {} name '{}' found.",
+                args.member_type,
+                args.signal.clone().unwrap_or_else(|| item_name.to_owned())
+            ),
+        ));
+    }
+
+    // Report every colliding match at once, in a stable order.
+    if ambiguous {
+        let mut collisions: Vec<_> = candidates.iter().collect();
+        collisions.sort();
+        let listing: String = collisions
+            .iter()
+            .map(|(iface, path, member)| {
+                let path = relative_to_manifest_dir(path);
+                format!("\n  - {member} of \"{iface}\" in {}", path.display())
+            })
+            .collect();
+        let (suggestion, _, _) = collisions[0];
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            format!(
+                "Multiple interfaces with the same {} name. Please disambiguate:{listing}\n\
+                 For example, add `interface: \"{suggestion}\"`.",
+                args.member_type
+            ),
+        ));
+    }
+
+    // With `first_match`, deterministically pick the first match by (interface, file) order.
+    if args.first_match {
+        let (iface, path, signal) = candidates
+            .iter()
+            .min()
+            .expect("A match should have been found in search loop.");
+        interface_name = Some(iface.clone());
+        xml_file_path = Some(path.clone());
+        signal_name = Some(signal.clone());
+    }
+
+    Ok(Some(ResolvedMember {
+        interface_name: interface_name.expect("Interface should have been found in search loop."),
+        signal_name: signal_name.expect("Signal should have been found in search loop."),
+        xml_file_path: xml_file_path.expect("XML file path should be found in search loop."),
+        candidates,
+    }))
+}
+
+/// `path` relative to the directory of the crate being compiled, so messages naming it do not
+/// depend on where the crate is checked out. Paths outside the crate are reached with `..`.
+/// Relative paths, and those sharing no directory with the crate but the root, are kept as
+/// they are.
+fn relative_to_manifest_dir(path: &Path) -> PathBuf {
+    let Some(manifest_dir) = std::env::var_os("CARGO_MANIFEST_DIR")
+        .and_then(|manifest_dir| Path::new(&manifest_dir).canonicalize().ok())
+    else {
+        return path.to_path_buf();
+    };
+
+    let mut base = manifest_dir.as_path();
+    let mut relative = PathBuf::new();
+    while let Some(parent) = base.parent() {
+        if let Ok(rest) = path.strip_prefix(base) {
+            return relative.join(rest);
+        }
+        base = parent;
+        relative.push("..");
+    }
+
+    path.to_path_buf()
+}
+
+/// An argument of a member, by position or by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArgSelector {
+    Index(usize),
+    Name(String),
+}
+
+impl std::fmt::Display for ArgSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgSelector::Index(index) => write!(f, "{index}"),
+            ArgSelector::Name(name) => write!(f, "\"{name}\""),
+        }
+    }
+}
+
+/// What `#[validate]` generates to perform the validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ValidateMode {
+    // A `#[test]`
+    Test,
+    // A function returning the mismatch
+    Fn,
+}
+
+/// How the name of a type is matched against signal names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NameMatch {
+    // The type's name contains the signal's name
+    Contains,
+    // The type's name is the signal's name
+    Exact,
+    // The type's name ends with the signal's name
+    Suffix,
+}
+
+impl ValidateArgs {
+    /// Whether the interface `name` is the one given by `interface`, if any, with or without
+    /// `prefix`.
+    fn interface_matches(&self, name: &str) -> bool {
+        match (&self.interface, &self.prefix) {
+            (None, _) => true,
+            (Some(interface), _) if name == interface => true,
+            (Some(interface), Some(prefix)) => {
+                name.strip_prefix(prefix.as_str()) == Some(interface.as_str())
+            }
+            (Some(_), None) => false,
+        }
+    }
+
+    /// Check that the arguments specific to a kind of member apply to `member_type`.
+    ///
+    /// This runs once the member type is known, which for `member` is after it is looked up in
+    /// XML.
+    fn check_member_type(&self, span: proc_macro2::Span) -> Result<()> {
+        let error = |message| Err(syn::Error::new(span, message));
+
+        if self.member_type == MemberType::Property
+            && (self.strict_wrapping || self.allow_trailing_metadata)
+        {
+            return error(
+                "`strict_wrapping` and `allow_trailing_metadata` do not apply to properties.",
+            );
+        }
+
+        if self.direction_given && self.member_type != MemberType::Method {
+            return error("`direction` only applies to methods.");
+        }
+
+        if self.signal_signature_annotation.is_some() && self.member_type != MemberType::Signal {
+            return error("`signal_signature_annotation` only applies to signals.");
+        }
+
+        if self.arg.is_some() && self.member_type == MemberType::Property {
+            return error("`arg` does not apply to properties.");
+        }
+
+        if self.require_arg_names && self.member_type != MemberType::Signal {
+            return error("`require_arg_names` only applies to signals.");
+        }
+
+        Ok(())
+    }
+}
+
+impl NameMatch {
+    fn matches(self, item_name: &str, member_name: &str) -> bool {
+        match self {
+            NameMatch::Contains => item_name.contains(member_name),
+            NameMatch::Exact => item_name == member_name,
+            NameMatch::Suffix => item_name.ends_with(member_name),
+        }
+    }
+}
+
+/// The message bus of a running service.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LiveBus {
+    Session,
+    System,
+}
+
+/// A running service to introspect.
+#[derive(Clone)]
+struct LiveTarget {
+    bus: LiveBus,
+    service: String,
+    path: String,
+}
+
+/// The kind of interface member a type is validated against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MemberType {
+    Signal,
+    Method,
+    Property,
+}
+
+impl std::fmt::Display for MemberType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MemberType::Signal => write!(f, "signal"),
+            MemberType::Method => write!(f, "method"),
+            MemberType::Property => write!(f, "property"),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ValidateArgs {
+    // Optional paths to XML directories
+    xml: Vec<PathBuf>,
+
+    // Optional introspection XML, instead of XML files
+    xml_str: Option<String>,
+
+    // Optional filename pattern of the XML files to consider
+    file_filter: Option<String>,
+
+    // Optional interface name
+    interface: Option<String>,
+
+    // Optional prefix that `interface` omits
+    prefix: Option<String>,
+
+    // Optional path to a constant holding the interface name
+    interface_const: Option<syn::Path>,
+
+    // Optional signal name, or member name with `member_type`
+    signal: Option<String>,
+
+    // Optional single argument of the member to validate against
+    arg: Option<ArgSelector>,
+
+    // Fields of the struct to leave out of the comparison
+    skip_fields: Vec<LitStr>,
+
+    // How the type's name is matched against signal names
+    name_match: NameMatch,
+
+    // Optional name of the generated test
+    test_name: Option<String>,
+
+    // Whether to generate a test or a function
+    mode: ValidateMode,
+
+    // Optional attribute to mark the generated test with, instead of `test`
+    test_attr: Option<syn::Meta>,
+
+    // Mark the generated test `#[ignore]`
+    ignore: bool,
+
+    // Record the resolved interface and member as associated constants
+    emit_const: bool,
+
+    // Optional literal signature to compare against instead of XML
+    signature: Option<String>,
+
+    // Kind of interface member to validate against
+    member_type: MemberType,
+
+    // Look up the kind of member named by `member` in XML, as none was given
+    infer_member_type: bool,
+
+    // Direction of the method arguments to validate against
+    direction: ArgDirection,
+
+    // Whether `direction` was given, rather than defaulting to in-args
+    direction_given: bool,
+
+    // Pick the first match, by sorted order, instead of failing on ambiguity
+    first_match: bool,
+
+    // Validate against every match instead of failing on ambiguity
+    all_matches: bool,
+
+    // Generate nothing, instead of failing, if no signal matches
+    skip_missing: bool,
+
+    // Claim the signal, no other type may claim it
+    exclusive: bool,
+
+    // Do not match a struct against flat signal arguments
+    strict_wrapping: bool,
+
+    // Permit one trailing `a{sv}` argument the struct omits
+    allow_trailing_metadata: bool,
+
+    // Let `v` fields match any type in XML
+    allow_value_fields: bool,
+
+    // Compare top-level fields in any order
+    any_field_order: bool,
+
+    // Print both signatures before comparing them
+    print: bool,
+
+    // Permit trailing struct fields the signal omits
+    allow_extra_trailing: bool,
+
+    // Also compare the XML to a stored snapshot
+    double_check: bool,
+
+    // Optional name of a signal annotation holding the body signature
+    signal_signature_annotation: Option<String>,
+
+    // Strip whitespace from `type` attributes before parsing
+    trim_types: bool,
+
+    // Check the XML against the introspection DTD
+    #[cfg_attr(not(feature = "dtd"), allow(dead_code))]
+    validate_dtd: bool,
+
+    // Optional running service to introspect, instead of XML files
+    live: Option<LiveTarget>,
+
+    // Assert the type implements `Serialize` and `Deserialize`
+    require_serde: bool,
+
+    // Assert all arguments of the signal are named
+    require_arg_names: bool,
+}
+
+/// The input of `validate_all!`: the arguments of `#[validate]`, then the types to validate.
+struct ValidateAllInput {
+    args: ValidateArgs,
+    types: Vec<Ident>,
+}
+
+impl syn::parse::Parse for ValidateAllInput {
+    fn parse(input: ParseStream) -> Result<Self> {
+        // Arguments are `name: value` pairs, types are bare identifiers.
+        let mut args = proc_macro2::TokenStream::new();
+        while input.peek(Ident::peek_any) && input.peek2(Token![:]) && !input.peek2(Token![::]) {
+            while !input.is_empty() && !input.peek(Token![,]) {
+                args.extend([input.parse::<proc_macro2::TokenTree>()?]);
+            }
+            if let Some(comma) = input.parse::<Option<Token![,]>>()? {
+                args.extend(quote!(#comma));
+            }
+        }
+
+        let args = syn::parse2::<ValidateArgs>(args)?;
+        let types = input.parse_terminated(Ident::parse, Token![,])?;
+        if types.is_empty() {
+            return Err(syn::Error::new(
+                input.span(),
+                "`validate_all!` should list at least one type.",
+            ));
+        }
+
+        Ok(ValidateAllInput {
+            args,
+            types: types.into_iter().collect(),
+        })
+    }
+}
+
+impl syn::parse::Parse for ValidateArgs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut xml = Vec::new();
+        let mut xml_str = None;
+        let mut file_filter = None;
+        let mut interface = None;
+        let mut prefix = None;
+        let mut interface_const = None;
+        let mut signal = None;
+        let mut arg = None;
+        let mut skip_fields = Vec::new();
+        let mut name_match = NameMatch::Contains;
+        let mut test_name = None;
+        let mut mode = ValidateMode::Test;
+        let mut test_attr = None;
+        let mut ignore = false;
+        let mut emit_const = false;
+        let mut signature = None;
+        let mut member_type = MemberType::Signal;
+        let mut member_type_given = false;
+        let mut member_given = false;
+        let mut method = None;
+        let mut property = None;
+        let mut direction = None;
+        let mut first_match = false;
+        let mut all_matches = false;
+        let mut skip_missing = false;
+        let mut exclusive = false;
+        let mut strict_wrapping = false;
+        let mut allow_trailing_metadata = false;
+        let mut allow_value_fields = false;
+        let mut any_field_order = false;
+        let mut print = false;
+        let mut allow_extra_trailing = false;
+        let mut double_check = false;
+        let mut signal_signature_annotation = None;
+        let mut trim_types = false;
+        let mut validate_dtd = false;
+        let mut bus = None;
+        let mut service = None;
+        let mut path = None;
+        let mut require_serde = false;
+        let mut require_arg_names = false;
+
+        // Arguments given so far, to reject one given twice rather than keep the last.
+        let mut seen = HashSet::new();
+
+        while !input.is_empty() {
+            // `match` is a keyword.
+            let ident = input.call(Ident::parse_any)?;
+
+            // `member` is an alias of `signal`.
+            let key = match ident.to_string().as_str() {
+                "member" => String::from("signal"),
+                key => key.to_owned(),
+            };
+            if !seen.insert(key) {
+                return Err(syn::Error::new(
+                    ident.span(),
+                    format!("argument `{ident}` specified twice."),
+                ));
+            }
+
+            member_given |= ident == "member";
+
+            match ident.to_string().as_str() {
+                "xml" => {
+                    input.parse::<Token![:]>()?;
+                    if input.peek(syn::token::Bracket) {
+                        let content;
+                        syn::bracketed!(content in input);
+                        let lits = content.parse_terminated(
+                            |input: ParseStream| input.parse::<LitStr>(),
+                            Token![,],
+                        )?;
+                        if lits.is_empty() {
+                            return Err(syn::Error::new(
+                                ident.span(),
+                                "`xml` should list at least one path.",
+                            ));
+                        }
+                        xml = lits.iter().map(xml_path).collect::<Result<_>>()?;
+                    } else {
+                        let lit = input.parse::<LitStr>()?;
+                        xml = vec![xml_path(&lit)?];
+                    }
+                }
+                "file_filter" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    file_filter = Some(lit.value());
+                }
+                "interface" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    interface = Some(lit.value());
+                }
+                "prefix" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    prefix = Some(lit.value());
+                }
+                "xml_str" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    xml_str = Some(lit.value());
+                }
+                "interface_const" => {
+                    input.parse::<Token![:]>()?;
+                    interface_const = Some(input.parse::<syn::Path>()?);
+                }
+                "signal" | "member" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    signal = Some(lit.value());
+                }
+                "skip_fields" => {
+                    input.parse::<Token![:]>()?;
+                    let content;
+                    syn::bracketed!(content in input);
+                    let lits = content.parse_terminated(
+                        |input: ParseStream| input.parse::<LitStr>(),
+                        Token![,],
+                    )?;
+                    skip_fields = lits.into_iter().collect();
+                }
+                "arg" => {
+                    input.parse::<Token![:]>()?;
+                    arg = if input.peek(syn::LitInt) {
+                        let lit = input.parse::<syn::LitInt>()?;
+                        Some(ArgSelector::Index(lit.base10_parse()?))
+                    } else {
+                        let lit = input.parse::<LitStr>()?;
+                        Some(ArgSelector::Name(lit.value()))
+                    };
+                }
+                "match" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    name_match = match lit.value().as_str() {
+                        "contains" => NameMatch::Contains,
+                        "exact" => NameMatch::Exact,
+                        "suffix" => NameMatch::Suffix,
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "`match` should be \"contains\", \"exact\" or \"suffix\".",
+                            ))
+                        }
+                    };
+                }
+                "test_name" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    if syn::parse_str::<Ident>(&lit.value()).is_err() {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            format!(
+                                "`test_name` should be a valid Rust identifier, found \"{}\".",
+                                lit.value()
+                            ),
+                        ));
+                    }
+                    test_name = Some(lit.value());
+                }
+                "signature" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    if zvariant::Signature::from_str(&lit.value()).is_err() {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            format!("`signature` \"{}\" is not a valid signature.", lit.value()),
+                        ));
+                    }
+                    signature = Some(lit.value());
+                }
+                "test_attr" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    match syn::parse_str::<syn::Meta>(&lit.value()) {
+                        Ok(meta) => test_attr = Some(meta),
+                        Err(e) => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                format!(
+                                    "`test_attr` should be a valid attribute, found \"{}\": {e}",
+                                    lit.value()
+                                ),
+                            ))
+                        }
+                    }
+                }
+                "ignore" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    ignore = lit.value();
+                }
+                "emit_const" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    emit_const = lit.value();
+                }
+                "mode" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    mode = match lit.value().as_str() {
+                        "test" => ValidateMode::Test,
+                        "fn" => ValidateMode::Fn,
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "`mode` should be \"test\" or \"fn\".",
+                            ))
+                        }
+                    };
+                }
+                "method" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    method = Some(lit.value());
+                }
+                "property" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    property = Some(lit.value());
+                }
+                "direction" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    direction = match lit.value().as_str() {
+                        "in" => Some(ArgDirection::In),
+                        "out" => Some(ArgDirection::Out),
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "`direction` should be \"in\" or \"out\".",
+                            ))
+                        }
+                    };
+                }
+                "member_type" => {
+                    member_type_given = true;
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    member_type = match lit.value().as_str() {
+                        "signal" => MemberType::Signal,
+                        "method" => MemberType::Method,
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "`member_type` should be \"signal\" or \"method\".",
+                            ))
+                        }
+                    };
+                }
+                "on_missing" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    skip_missing = match lit.value().as_str() {
+                        "error" => false,
+                        "skip" => true,
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "`on_missing` should be \"error\" or \"skip\".",
+                            ))
+                        }
+                    };
+                }
+                "first_match" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    first_match = lit.value();
+                }
+                "all_matches" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    all_matches = lit.value();
+                }
+                "exclusive" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    exclusive = lit.value();
+                }
+                "strict_wrapping" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    strict_wrapping = lit.value();
+                }
+                "allow_trailing_metadata" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    allow_trailing_metadata = lit.value();
+                }
+                "order" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    any_field_order = match lit.value().as_str() {
+                        "strict" => false,
+                        "any" => true,
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "`order` should be \"strict\" or \"any\".",
+                            ))
+                        }
+                    };
+                }
+                "allow_value_fields" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    allow_value_fields = lit.value();
+                }
+                "allow_extra_trailing" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    allow_extra_trailing = lit.value();
+                }
+                "print" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    print = lit.value();
+                }
+                "double_check" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    double_check = lit.value();
+                }
+                "signal_signature_annotation" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    signal_signature_annotation = Some(lit.value());
+                }
+                "trim_types" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    trim_types = lit.value();
+                }
+                "validate_dtd" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    if lit.value() && cfg!(not(feature = "dtd")) {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "`validate_dtd` requires the `dtd` feature of zbus-lockstep-macros.",
+                        ));
+                    }
+                    validate_dtd = lit.value();
+                }
+                "bus" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    bus = match lit.value().as_str() {
+                        "session" => Some(LiveBus::Session),
+                        "system" => Some(LiveBus::System),
+                        _ => {
+                            return Err(syn::Error::new(
+                                lit.span(),
+                                "`bus` should be \"session\" or \"system\".",
+                            ))
+                        }
+                    };
+                }
+                "service" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    service = Some(lit.value());
+                }
+                "path" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitStr>()?;
+                    path = Some(lit.value());
+                }
+                "require_serde" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    require_serde = lit.value();
+                }
+                "require_arg_names" => {
+                    input.parse::<Token![:]>()?;
+                    let lit = input.parse::<LitBool>()?;
+                    require_arg_names = lit.value();
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        ident.span(),
+                        format!("Unexpected argument: {ident}"),
+                    ))
+                }
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        if emit_const && (all_matches || signature.is_some()) {
+            return Err(syn::Error::new(
+                input.span(),
+                "`emit_const` cannot be combined with `all_matches` or `signature`.",
+            ));
+        }
+
+        if all_matches && (first_match || interface_const.is_some()) {
+            return Err(syn::Error::new(
+                input.span(),
+                "`all_matches` cannot be combined with `first_match` or `interface_const`.",
+            ));
+        }
+
+        if interface.is_some() && interface_const.is_some() {
+            return Err(syn::Error::new(
+                input.span(),
+                "`interface` and `interface_const` are mutually exclusive.",
+            ));
+        }
+
+        if exclusive && interface_const.is_some() {
+            return Err(syn::Error::new(
+                input.span(),
+                "`exclusive` cannot be combined with `interface_const`.",
+            ));
+        }
+
+        if let Some(method) = method {
+            if signal.is_some() {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "`method` and `signal` are mutually exclusive.",
+                ));
+            }
+            member_type = MemberType::Method;
+            signal = Some(method);
+        }
+
+        if let Some(property) = property {
+            if signal.is_some() {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "`property` cannot be combined with `signal` or `method`.",
+                ));
+            }
+            member_type = MemberType::Property;
+            signal = Some(property);
+        }
+
+        if signal_signature_annotation.is_some() && allow_trailing_metadata {
+            return Err(syn::Error::new(
+                input.span(),
+                "`signal_signature_annotation` cannot be combined with `allow_trailing_metadata`.",
+            ));
+        }
+
+        if test_attr.is_some() && mode == ValidateMode::Fn {
+            return Err(syn::Error::new(
+                input.span(),
+                "`test_attr` does not apply to `mode: \"fn\"`.",
+            ));
+        }
+
+        if ignore && mode == ValidateMode::Fn {
+            return Err(syn::Error::new(
+                input.span(),
+                "`ignore` does not apply to `mode: \"fn\"`.",
+            ));
+        }
+
+        if signature.is_some() && (!xml.is_empty() || xml_str.is_some() || signal.is_some()) {
+            return Err(syn::Error::new(
+                input.span(),
+                "`signature` cannot be combined with `xml`, `xml_str` or a member name.",
+            ));
+        }
+
+        if prefix.is_some() && interface.is_none() {
+            return Err(syn::Error::new(input.span(), "`prefix` needs `interface`."));
+        }
+
+        if xml_str.is_some() && !xml.is_empty() {
+            return Err(syn::Error::new(
+                input.span(),
+                "`xml` and `xml_str` cannot be combined.",
+            ));
+        }
+
+        let live = match (bus, service, path) {
+            (None, None, None) => None,
+            _ if cfg!(not(feature = "live-introspection")) => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "`bus`, `service` and `path` require the `live-introspection` feature of \
+                     zbus-lockstep-macros.",
+                ))
+            }
+            (Some(bus), Some(service), Some(path)) => Some(LiveTarget { bus, service, path }),
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "`bus`, `service` and `path` must be given together.",
+                ))
+            }
+        };
+
+        if live.is_some() && (!xml.is_empty() || xml_str.is_some()) {
+            return Err(syn::Error::new(
+                input.span(),
+                "`bus`, `service` and `path` cannot be combined with `xml` or `xml_str`.",
+            ));
+        }
+
+        if live.is_some() && (interface.is_none() || signal.is_none()) {
+            return Err(syn::Error::new(
+                input.span(),
+                "`bus`, `service` and `path` need `interface` and a member name.",
+            ));
+        }
+
+        if live.is_some() && all_matches {
+            return Err(syn::Error::new(
+                input.span(),
+                "`all_matches` cannot be combined with live introspection.",
+            ));
+        }
+
+        if arg.is_some()
+            && (strict_wrapping
+                || allow_trailing_metadata
+                || signal_signature_annotation.is_some()
+                || live.is_some())
+        {
+            return Err(syn::Error::new(
+                input.span(),
+                "`arg` cannot be combined with `strict_wrapping`, `allow_trailing_metadata`, \
+                 `signal_signature_annotation` or live introspection.",
+            ));
+        }
+
+        let args = ValidateArgs {
+            xml,
+            xml_str,
+            file_filter,
+            interface: match (interface, &prefix) {
+                // A running service is asked by full name.
+                (Some(interface), Some(prefix))
+                    if live.is_some() && !interface.starts_with(prefix.as_str()) =>
+                {
+                    Some(format!("{prefix}{interface}"))
+                }
+                (interface, _) => interface,
+            },
+            prefix,
+            interface_const,
+            signal,
+            arg,
+            skip_fields,
+            name_match,
+            test_name,
+            mode,
+            test_attr,
+            ignore,
+            emit_const,
+            signature,
+            member_type,
+            infer_member_type: member_given && !member_type_given,
+            direction_given: direction.is_some(),
+            direction: direction.unwrap_or(ArgDirection::In),
+            first_match,
+            all_matches,
+            skip_missing,
+            exclusive,
+            strict_wrapping,
+            allow_trailing_metadata,
+            allow_value_fields,
+            any_field_order,
+            print,
+            allow_extra_trailing,
+            double_check,
+            signal_signature_annotation,
+            trim_types,
+            validate_dtd,
+            live,
+            require_serde,
+            require_arg_names,
+        };
+
+        // An inferred member type is checked once it is known.
+        if !args.infer_member_type {
+            args.check_member_type(input.span())?;
+        }
+
+        Ok(args)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use std::sync::Arc;
+
+    use quote::quote;
+    use zbus_xml::ArgDirection;
+
+    use crate::{
+        collect_xml_files, find_member_file, infer_member_type, member_signature, parse_xml_file,
+        read_xml_files, relative_to_manifest_dir, resolve_signal, validation_item, LiveBus,
+        MemberType, NameMatch, ValidateArgs, ValidateMode, XmlFiles,
+    };
+
+    #[test]
+    fn test_xml_dir_is_read_and_parsed_once() {
+        let dir = Path::new("../xml/methods");
+        let first = read_xml_files(dir, None).unwrap();
+        let (path, xml) = first.iter().next().unwrap();
+        let first_node = parse_xml_file(path, xml).unwrap();
+
+        // As 50 `#[validate]` invocations for the same directory would.
+        for _ in 0..50 {
+            let xml_files = read_xml_files(dir, None).unwrap();
+            assert!(Arc::ptr_eq(&first, &xml_files));
+
+            let node = parse_xml_file(path, &xml_files[path]).unwrap();
+            assert!(Arc::ptr_eq(&first_node, &node));
+        }
+    }
+
+    #[test]
+    fn test_xml_dir_is_read_again_once_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("org.example.Changing.xml");
+        std::fs::write(&path, "<node/>").unwrap();
+
+        let first = read_xml_files(dir.path(), None).unwrap();
+        assert_eq!(first[&path], "<node/>");
+
+        // An edit changes the length, an added file the listing.
+        std::fs::write(&path, "<node></node>").unwrap();
+        let edited = read_xml_files(dir.path(), None).unwrap();
+        assert!(!Arc::ptr_eq(&first, &edited));
+        assert_eq!(edited[&path], "<node></node>");
+
+        let added = dir.path().join("org.example.Added.xml");
+        std::fs::write(&added, "<node/>").unwrap();
+        let xml_files = read_xml_files(dir.path(), None).unwrap();
+        assert_eq!(xml_files.len(), 2);
+        assert!(Arc::ptr_eq(
+            &xml_files,
+            &read_xml_files(dir.path(), None).unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_collect_xml_files_skips_files_without_extension() {
+        let xml_files = collect_xml_files(Path::new("../xml/extensionless"), None).unwrap();
+
+        let names: Vec<_> = xml_files
+            .keys()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["org.example.Plain.xml"]);
+    }
+
+    #[test]
+    fn test_collect_xml_files_reads_compressed_files() {
+        let result = collect_xml_files(Path::new("../xml/gzip"), None);
+        if cfg!(feature = "gzip") {
+            let xml_files = result.unwrap();
+            let xml = &xml_files[Path::new("../xml/gzip/org.example.Compressed.xml.gz")];
+            assert!(xml.contains(r#"<signal name="Compressed">"#));
+        } else {
+            let err = result.expect_err("compressed XML should need the `gzip` feature");
+            assert!(err
+                .to_string()
+                .contains("compressed XML is not supported, enable the `gzip` feature"));
+        }
+    }
+
+    #[test]
+    fn test_find_member_file_picks_first_of_identical_declarations() {
+        let xml = r#"
+            <node>
+              <interface name="org.example.Player">
+                <signal name="Seeked"><arg type="x"/></signal>
+              </interface>
+            </node>
+        "#;
+        let xml_files = XmlFiles::from([
+            (PathBuf::from("vendor/b/player.xml"), xml.to_owned()),
+            (PathBuf::from("vendor/a/player.xml"), xml.to_owned()),
+        ]);
+
+        let path = find_member_file(
+            &xml_files,
+            "org.example.Player",
+            MemberType::Signal,
+            "Seeked",
+        )
+        .unwrap();
+        assert_eq!(path, Some(PathBuf::from("vendor/a/player.xml")));
+    }
+
+    #[test]
+    fn test_find_member_file_rejects_conflicting_declarations() {
+        let declaring = |arg_type: &str| {
+            format!(
+                r#"<node>
+                  <interface name="org.example.Player">
+                    <signal name="Seeked"><arg type="{arg_type}"/></signal>
+                  </interface>
+                </node>"#
+            )
+        };
+        let xml_files = XmlFiles::from([
+            (PathBuf::from("b.xml"), declaring("u")),
+            (PathBuf::from("a.xml"), declaring("x")),
+        ]);
+
+        let err = find_member_file(
+            &xml_files,
+            "org.example.Player",
+            MemberType::Signal,
+            "Seeked",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Conflicting declarations of signal \"Seeked\" of interface \"org.example.Player\" \
+             in \"a.xml\" and \"b.xml\"."
+        );
+    }
+
+    #[test]
+    fn test_parse_xml_file_names_unparsable_file() {
+        let path = Path::new("../xml/file_filter/settings.xml");
+        let xml = std::fs::read_to_string(path).unwrap();
+
+        let err = parse_xml_file(path, &xml).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Failed to parse XML file: \"../xml/file_filter/settings.xml\""));
+    }
+
+    #[test]
+    fn test_member_signature_tells_conflicting_duplicates_apart() {
+        let xml = std::fs::read_to_string("../xml/duplicates/org.example.Moves.xml").unwrap();
+        let signature = |interface| {
+            member_signature(
+                &xml,
+                MemberType::Signal,
+                ArgDirection::In,
+                interface,
+                "Moved",
+            )
+            .unwrap()
+        };
+
+        let left = signature("org.example.Left");
+        let right = signature("org.example.Right");
+        assert!(!zbus_lockstep::signatures_are_eq(&left, &right));
+    }
+
+    #[test]
+    fn test_collect_xml_files_selects_files_matching_resolved_glob() {
+        let pattern =
+            zbus_lockstep::resolve_xml_path(Some("../xml/glob/**/org.freedesktop.*.xml")).unwrap();
+        let xml_files = collect_xml_files(&pattern, None).unwrap();
+
+        let mut names: Vec<_> = xml_files
+            .keys()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec!["org.freedesktop.Alpha.xml", "org.freedesktop.Beta.xml"]
+        );
+    }
+
+    #[test]
+    fn test_validate_args_reject_duplicate_arguments() {
+        for (args, duplicate) in [
+            (r#"xml: "a", xml: "b""#, "xml"),
+            (
+                r#"interface: "org.example.A", interface: "org.example.B""#,
+                "interface",
+            ),
+            (r#"signal: "A", signal: "B""#, "signal"),
+            (r#"signal: "A", member: "B""#, "member"),
+        ] {
+            let err = syn::parse_str::<ValidateArgs>(args)
+                .err()
+                .expect("duplicate arguments should be rejected");
+            assert_eq!(
+                err.to_string(),
+                format!("argument `{duplicate}` specified twice.")
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_args_match() {
+        let args = syn::parse_str::<ValidateArgs>(r#"match: "suffix""#).unwrap();
+        assert_eq!(args.name_match, NameMatch::Suffix);
+        assert!(args.name_match.matches("OnStateChanged", "StateChanged"));
+        assert!(!args.name_match.matches("StateMachineConfig", "State"));
+
+        let err = syn::parse_str::<ValidateArgs>(r#"match: "prefix""#)
+            .err()
+            .expect("an unknown `match` should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "`match` should be \"contains\", \"exact\" or \"suffix\"."
+        );
+    }
+
+    #[test]
+    fn test_validate_args_reject_xml_with_xml_str() {
+        let err = syn::parse_str::<ValidateArgs>(r#"xml: "xml", xml_str: "<node/>""#)
+            .err()
+            .expect("`xml` and `xml_str` together should be rejected");
+        assert_eq!(err.to_string(), "`xml` and `xml_str` cannot be combined.");
+    }
+
+    #[test]
+    fn test_validate_args_live_introspection() {
+        let args = r#"bus: "session", service: "org.example.Node", path: "/org/example/Node",
+            interface: "org.example.Node", signal: "RemoveNode""#;
+        let result = syn::parse_str::<ValidateArgs>(args);
+        if cfg!(feature = "live-introspection") {
+            let live = result.ok().and_then(|args| args.live).unwrap();
+            assert_eq!(live.bus, LiveBus::Session);
+            assert_eq!(live.service, "org.example.Node");
+            assert_eq!(live.path, "/org/example/Node");
+
+            let err = syn::parse_str::<ValidateArgs>(r#"bus: "session", path: "/""#)
+                .err()
+                .expect("an incomplete live target should be rejected");
+            assert_eq!(
+                err.to_string(),
+                "`bus`, `service` and `path` must be given together."
+            );
+        } else {
+            let err = result
+                .err()
+                .expect("live introspection should need its feature");
+            assert!(err.to_string().contains("`live-introspection` feature"));
+        }
+    }
+
+    // Two interfaces declaring `StateChanged` with different bodies, and one `Seeked` signal.
+    fn player_xml_files() -> XmlFiles {
+        let xml = r#"
+            <node>
+              <interface name="org.example.Player">
+                <signal name="StateChanged"><arg type="s"/><arg type="x"/></signal>
+                <signal name="Seeked"><arg type="x"/></signal>
+              </interface>
+              <interface name="org.example.Recorder">
+                <signal name="StateChanged"><arg type="s"/><arg type="b"/></signal>
+              </interface>
+            </node>
+        "#;
+        XmlFiles::from([(PathBuf::from("player.xml"), xml.to_owned())])
+    }
+
+    #[test]
+    fn test_resolve_signal_by_name() {
+        let args = syn::parse_str::<ValidateArgs>("").unwrap();
+        let resolved = resolve_signal(&player_xml_files(), "SeekedEvent", &args)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(resolved.interface_name, "org.example.Player");
+        assert_eq!(resolved.signal_name, "Seeked");
+        assert_eq!(resolved.xml_file_path, Path::new("player.xml"));
+    }
+
+    #[test]
+    fn test_resolve_signal_disambiguated_by_interface() {
+        let args = syn::parse_str::<ValidateArgs>(r#"interface: "org.example.Recorder""#).unwrap();
+        let resolved = resolve_signal(&player_xml_files(), "StateChangedEvent", &args)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(resolved.interface_name, "org.example.Recorder");
+        assert_eq!(resolved.signal_name, "StateChanged");
+    }
+
+    #[test]
+    fn test_relative_to_manifest_dir() {
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .canonicalize()
+            .unwrap();
+        assert_eq!(
+            relative_to_manifest_dir(&manifest_dir.join("src/lib.rs")),
+            Path::new("src/lib.rs")
+        );
+
+        let workspace_xml = manifest_dir
+            .parent()
+            .unwrap()
+            .join("xml/duplicates/Moves.xml");
+        assert_eq!(
+            relative_to_manifest_dir(&workspace_xml),
+            Path::new("../xml/duplicates/Moves.xml")
+        );
+
+        assert_eq!(
+            relative_to_manifest_dir(Path::new("player.xml")),
+            Path::new("player.xml")
+        );
+    }
+
+    #[test]
+    fn test_resolve_signal_ambiguous() {
+        let args = syn::parse_str::<ValidateArgs>("").unwrap();
+        let err = resolve_signal(&player_xml_files(), "StateChangedEvent", &args).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Multiple interfaces with the same signal name. Please disambiguate:\n  \
+             - StateChanged of \"org.example.Player\" in player.xml\n  \
+             - StateChanged of \"org.example.Recorder\" in player.xml\n\
+             For example, add `interface: \"org.example.Player\"`."
+        );
+    }
+
+    #[test]
+    fn test_resolve_signal_explicit_name_ambiguous() {
+        let args = syn::parse_str::<ValidateArgs>(r#"signal: "StateChanged""#).unwrap();
+        let err = resolve_signal(&player_xml_files(), "PlaybackState", &args).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Multiple interfaces with the same signal name. Please disambiguate:\n  \
+             - StateChanged of \"org.example.Player\" in player.xml\n  \
+             - StateChanged of \"org.example.Recorder\" in player.xml\n\
+             For example, add `interface: \"org.example.Player\"`."
+        );
+    }
+
+    #[test]
+    fn test_resolve_signal_explicit_name_vendored() {
+        let vendored = r#"
+            <node>
+              <interface name="org.example.Player">
+                <signal name="Seeked"><arg type="x"/></signal>
+              </interface>
+            </node>
+        "#;
+        let mut xml_files = player_xml_files();
+        xml_files.insert(PathBuf::from("vendored.xml"), vendored.to_owned());
+
+        let args = syn::parse_str::<ValidateArgs>(r#"signal: "Seeked""#).unwrap();
+        let resolved = resolve_signal(&xml_files, "Position", &args)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(resolved.interface_name, "org.example.Player");
+        assert_eq!(resolved.signal_name, "Seeked");
+    }
+
+    #[test]
+    fn test_resolve_signal_all_matches() {
+        let args = syn::parse_str::<ValidateArgs>("all_matches: true").unwrap();
+        let resolved = resolve_signal(&player_xml_files(), "StateChangedEvent", &args)
+            .unwrap()
+            .unwrap();
+
+        let mut interfaces: Vec<_> = resolved
+            .candidates
+            .iter()
+            .map(|(iface, _, _)| iface.as_str())
+            .collect();
+        interfaces.sort();
+        assert_eq!(interfaces, ["org.example.Player", "org.example.Recorder"]);
+    }
+
+    #[test]
+    fn test_validate_args_reject_emit_const_with_signature() {
+        let err = syn::parse_str::<ValidateArgs>(r#"signature: "(so)", emit_const: true"#)
+            .err()
+            .expect("`emit_const` and `signature` together should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "`emit_const` cannot be combined with `all_matches` or `signature`."
+        );
+    }
+
+    #[test]
+    fn test_validate_args_reject_all_with_first_match() {
+        let err = syn::parse_str::<ValidateArgs>("all_matches: true, first_match: true")
+            .err()
+            .expect("`all_matches` and `first_match` together should be rejected");
+        assert_eq!(
+            err.to_string(),
+            "`all_matches` cannot be combined with `first_match` or `interface_const`."
+        );
+    }
+
+    #[test]
+    fn test_infer_member_type() {
+        let xml = r#"
+            <node>
+              <interface name="org.example.Events">
+                <signal name="Refresh"><arg type="s"/></signal>
+              </interface>
+              <interface name="org.example.Control">
+                <method name="Refresh"><arg type="u" direction="in"/></method>
+                <property name="Theme" type="s" access="read"/>
+              </interface>
+            </node>
+        "#;
+        let xml_files = XmlFiles::from([(PathBuf::from("members.xml"), xml.to_owned())]);
+        let infer = |args: &str, member_name: &str| {
+            let args = syn::parse_str::<ValidateArgs>(args).unwrap();
+            assert!(args.infer_member_type);
+            infer_member_type(&xml_files, member_name, &args).unwrap()
+        };
+
+        assert_eq!(infer(r#"member: "Refresh""#, "Refresh"), MemberType::Signal);
+        assert_eq!(
+            infer(
+                r#"interface: "org.example.Control", member: "Refresh""#,
+                "Refresh"
+            ),
+            MemberType::Method
+        );
+        assert_eq!(infer(r#"member: "Theme""#, "Theme"), MemberType::Property);
+        assert_eq!(infer(r#"member: "Missing""#, "Missing"), MemberType::Signal);
+
+        // An explicit `member_type` is not second-guessed.
+        let args =
+            syn::parse_str::<ValidateArgs>(r#"member_type: "method", member: "Refresh""#).unwrap();
+        assert!(!args.infer_member_type);
+    }
+
+    #[test]
+    fn test_resolve_signal_not_found() {
+        let args = syn::parse_str::<ValidateArgs>(r#"signal: "Stopped""#).unwrap();
+        let err = resolve_signal(&player_xml_files(), "StoppedEvent", &args).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "No interface matching signal name 'Stopped' found."
+        );
+    }
+
+    #[test]
+    fn test_resolve_signal_by_interface_and_signal() {
+        let args = syn::parse_str::<ValidateArgs>(
+            r#"interface: "org.example.Player", signal: "StateChanged""#,
+        )
+        .unwrap();
+        let resolved = resolve_signal(&player_xml_files(), "Event", &args)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(resolved.interface_name, "org.example.Player");
+        assert_eq!(resolved.signal_name, "StateChanged");
+    }
+
+    #[test]
+    fn test_resolve_signal_interface_not_found() {
+        let args = syn::parse_str::<ValidateArgs>(
+            r#"interface: "org.example.Missing", signal: "StateChanged""#,
+        )
+        .unwrap();
+        let err = resolve_signal(&player_xml_files(), "Event", &args).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Interface \"org.example.Missing\" not found."
+        );
+    }
+
+    #[test]
+    fn test_resolve_signal_by_prefixed_interface() {
+        let args =
+            syn::parse_str::<ValidateArgs>(r#"prefix: "org.example.", interface: "Recorder""#)
+                .unwrap();
+        let resolved = resolve_signal(&player_xml_files(), "StateChangedEvent", &args)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(resolved.interface_name, "org.example.Recorder");
+    }
+
+    #[test]
+    fn test_interface_matches() {
+        let exact = syn::parse_str::<ValidateArgs>(r#"interface: "org.example.Player""#).unwrap();
+        assert!(exact.interface_matches("org.example.Player"));
+        assert!(!exact.interface_matches("Player"));
+
+        let prefixed =
+            syn::parse_str::<ValidateArgs>(r#"prefix: "org.example.", interface: "Player""#)
+                .unwrap();
+        assert!(prefixed.interface_matches("org.example.Player"));
+        assert!(!prefixed.interface_matches("org.other.Player"));
+        assert!(!prefixed.interface_matches("org.example.MediaPlayer"));
+    }
+
+    #[test]
+    fn test_prefix_needs_interface() {
+        let err = syn::parse_str::<ValidateArgs>(r#"prefix: "org.example.""#)
+            .err()
+            .expect("`prefix` without `interface` should be rejected");
+        assert_eq!(err.to_string(), "`prefix` needs `interface`.");
+    }
+
+    #[test]
+    fn test_resolve_signal_not_found_on_interface() {
+        let args = syn::parse_str::<ValidateArgs>(
+            r#"interface: "org.example.Recorder", signal: "Seeked""#,
+        )
+        .unwrap();
+        let err = resolve_signal(&player_xml_files(), "Event", &args).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "No signal 'Seeked' found on interface \"org.example.Recorder\"."
+        );
+    }
+
+    #[test]
+    fn test_validation_item_ignore() {
+        let name = syn::parse_str::<syn::Ident>("test_Event_type_signature").unwrap();
+        let ignored = validation_item(ValidateMode::Test, None, true, &name, &quote!({}));
+        assert!(ignored
+            .to_string()
+            .contains(r#"# [ignore = "lockstep validation"]"#));
+
+        let item = validation_item(ValidateMode::Test, None, false, &name, &quote!({}));
+        assert!(!item.to_string().contains("ignore"));
+    }
+
+    #[test]
+    fn test_resolve_signal_explicit_signal_ignores_type_name() {
+        // The name contains `StateChanged`, which alone would be ambiguous.
+        let args = syn::parse_str::<ValidateArgs>(r#"signal: "Seeked""#).unwrap();
+        let resolved = resolve_signal(&player_xml_files(), "StateChangedSeeked", &args)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(resolved.interface_name, "org.example.Player");
+        assert_eq!(resolved.signal_name, "Seeked");
+    }
+
+    #[test]
+    fn test_resolve_signal_skip_missing() {
+        let args =
+            syn::parse_str::<ValidateArgs>(r#"signal: "Stopped", on_missing: "skip""#).unwrap();
+        let resolved = resolve_signal(&player_xml_files(), "StoppedEvent", &args).unwrap();
+
+        assert!(resolved.is_none());
     }
 }
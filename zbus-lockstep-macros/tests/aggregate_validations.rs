@@ -0,0 +1,50 @@
+// Tests for `zbus_lockstep::aggregate_validations!`, which runs the functions generated by
+// `#[validate(mode: "fn")]` as a single test.
+
+use zbus_lockstep::aggregate_validations;
+use zbus_lockstep_macros::validate;
+use zvariant::{OwnedObjectPath, Type};
+
+#[validate(xml: "./xml", signal: "AddNode", mode: "fn")]
+#[derive(Debug, Type)]
+struct AddNodeEvent {
+    _name: String,
+    _path: OwnedObjectPath,
+}
+
+#[validate(xml: "./xml", signal: "RemoveNode", mode: "fn")]
+#[derive(Debug, Type)]
+struct RemoveNodeEvent {
+    _name: String,
+    _path: OwnedObjectPath,
+}
+
+#[validate(xml: "./xml", signal: "RemoveNode", mode: "fn")]
+#[derive(Debug, Type)]
+struct DriftedRemoveNode {
+    _name: String,
+    _id: u32,
+}
+
+#[validate(xml: "./xml", signal: "AddNode", mode: "fn")]
+#[derive(Debug, Type)]
+struct DriftedAddNode {
+    _name: String,
+}
+
+aggregate_validations!(
+    test_aggregated_validations,
+    [__validate_AddNodeEvent, __validate_RemoveNodeEvent],
+);
+
+// Every failure is reported, not just the first.
+aggregate_validations!(
+    #[should_panic(expected = "2 of 3 validations failed:\n\
+                               `__validate_DriftedRemoveNode`: Type drifted from XML")]
+    test_aggregated_validations_report_all_failures,
+    [
+        __validate_DriftedRemoveNode,
+        __validate_AddNodeEvent,
+        __validate_DriftedAddNode,
+    ]
+);
@@ -0,0 +1,58 @@
+// Tests that the generated code compiles without warnings and refers to its dependencies by
+// absolute paths, so local items named like them are not picked up instead.
+#![deny(warnings)]
+#![allow(unnameable_test_items)]
+
+use zbus_lockstep_macros::{validate, Lockstep};
+
+// Shadow the crates the generated code depends on.
+#[allow(dead_code)]
+mod zbus_lockstep {}
+#[allow(dead_code)]
+mod zvariant {}
+
+#[test]
+fn test_validate_macro_hygiene() {
+    #[validate(xml: "./xml/newtype", signal: "NameChanged")]
+    #[derive(Debug, ::zvariant::Type)]
+    #[allow(dead_code)]
+    struct NameChanged(String);
+
+    #[validate(signature: "(so)")]
+    #[derive(Debug, ::zvariant::Type)]
+    #[allow(dead_code)]
+    struct Node {
+        name: String,
+        path: ::zvariant::OwnedObjectPath,
+    }
+
+    #[validate(xml: "./xml/method_replies", method: "GetTitle", direction: "out")]
+    #[derive(Debug, ::zvariant::Type)]
+    #[allow(dead_code)]
+    struct Title(String);
+
+    #[validate(xml: "./xml/lockstep_derive", property: "Volume", trim_types: true)]
+    #[derive(Debug, ::zvariant::Type)]
+    #[allow(dead_code)]
+    struct Volume(f64);
+
+    #[validate(xml: "./xml/newtype", signal: "NameChanged", mode: "fn")]
+    #[derive(Debug, ::zvariant::Type)]
+    #[allow(dead_code)]
+    struct NameChangedFn(String);
+
+    // now call the tests generated by the `validate` macro
+    test_NameChanged_type_signature();
+    test_Node_type_signature();
+    test_Title_type_signature();
+    test_Volume_type_signature();
+    __validate_NameChangedFn().unwrap();
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Lockstep)]
+#[lockstep(xml = "./xml/lockstep_derive")]
+struct PlayerVolume {
+    #[lockstep(interface = "org.example.Player", property = "Volume")]
+    volume: f64,
+}
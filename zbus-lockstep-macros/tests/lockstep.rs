@@ -0,0 +1,36 @@
+// Tests for the `Lockstep` derive, which generates a validation test for each marked field.
+#![allow(unnameable_test_items)]
+
+use zbus_lockstep_macros::Lockstep;
+use zvariant::OwnedObjectPath;
+
+#[allow(dead_code)]
+#[derive(Debug, Lockstep)]
+#[lockstep(xml = "./xml/lockstep_derive")]
+struct PlayerStatus {
+    #[lockstep(interface = "org.example.Player", property = "Volume")]
+    volume: f64,
+    #[lockstep(interface = "org.example.Player", property = "Playlist")]
+    playlist: Vec<String>,
+    #[lockstep(interface = "org.example.Player", signal = "Seeked", arg = "track")]
+    track: OwnedObjectPath,
+    #[lockstep(interface = "org.example.Player", signal = "Seeked", arg = "position")]
+    position: i64,
+    // Not validated.
+    muted: bool,
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_lockstep_derive_mismatch() {
+    #[allow(dead_code)]
+    #[derive(Debug, Lockstep)]
+    #[lockstep(xml = "./xml/lockstep_derive")]
+    struct DriftedStatus {
+        #[lockstep(interface = "org.example.Player", property = "Volume")]
+        volume: u32,
+    }
+
+    // now call the test generated by the `Lockstep` derive
+    test_DriftedStatus_volume_type_signature();
+}
@@ -0,0 +1,25 @@
+// Tests for `validate_from_manifest!`, which generates the validation tests for the types
+// listed in `lockstep_manifest.toml`.
+
+use zbus_lockstep_macros::validate_from_manifest;
+use zvariant::Type;
+
+mod events {
+    use zvariant::{OwnedObjectPath, Type};
+
+    #[derive(Debug, Type)]
+    pub struct AddNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+}
+
+#[derive(Debug, Type)]
+#[allow(dead_code)]
+struct AlertEvent {
+    urgent: bool,
+    color: String,
+    volume: f64,
+}
+
+validate_from_manifest!("tests/lockstep_manifest.toml");
@@ -0,0 +1,47 @@
+// Tests for `validate_from_manifest!` with `aggregate = true`, which generates a single
+// test, `lockstep_manifest_signatures`, for all types listed in the manifest, and the
+// functions listed in its `validations`.
+
+use zbus_lockstep_macros::validate_from_manifest;
+use zvariant::Type;
+
+mod events {
+    use zvariant::{OwnedObjectPath, Type};
+
+    #[derive(Debug, Type)]
+    pub struct AddNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+}
+
+#[derive(Debug, Type)]
+#[allow(dead_code)]
+struct AlertEvent {
+    urgent: bool,
+    color: String,
+    volume: f64,
+}
+
+// Validated with `#[validate(mode: "fn")]`, and run by the manifest's test.
+mod checks {
+    use zbus_lockstep_macros::validate;
+    use zvariant::{OwnedObjectPath, Type};
+
+    #[validate(xml: "./xml", signal: "RemoveNode", mode: "fn")]
+    #[derive(Debug, Type)]
+    pub struct RemoveNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    #[validate(xml: "./xml", signal: "Alert", mode: "fn", test_name: "validate_alert")]
+    #[derive(Debug, Type)]
+    pub struct AlertEvent {
+        _urgent: bool,
+        _color: String,
+        _volume: f64,
+    }
+}
+
+validate_from_manifest!("tests/lockstep_manifest_aggregate.toml");
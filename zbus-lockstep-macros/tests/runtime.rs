@@ -0,0 +1,66 @@
+// Tests that the test generated by `validate` reads the XML file it found and compares the
+// type against the file's contents when it runs, rather than only compiling.
+#![allow(unnameable_test_items)]
+
+use zbus_lockstep_macros::validate;
+use zvariant::{OwnedObjectPath, Type};
+
+#[test]
+fn test_generated_test_passes_against_file_contents() {
+    #[validate(xml: "./xml", interface: "org.example.Node", signal: "RemoveNode")]
+    #[derive(Debug, Type)]
+    struct RemoveNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // The signature is only available from the file's contents, so this passing means the
+    // file, not its path, was parsed.
+    test_RemoveNodeEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_generated_test_fails_against_file_contents() {
+    #[validate(xml: "./xml", interface: "org.example.Node", signal: "RemoveNode")]
+    #[derive(Debug, Type)]
+    struct MismatchedRemoveNodeEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // Reaching the comparison, rather than failing to parse XML, shows that the contents of
+    // the file were read.
+    test_MismatchedRemoveNodeEvent_type_signature();
+}
+
+#[test]
+fn test_generated_test_passes_against_all_matches() {
+    // `Changed` is declared by three interfaces in this file, all with the same body.
+    #[validate(
+        xml: "./xml/all_matches/org.example.Stores.xml",
+        signal: "Changed",
+        all_matches: true
+    )]
+    #[derive(Debug, Type)]
+    struct StoreChanged {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    test_StoreChanged_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "\"Changed\" of interface \"org.example.Photos\"")]
+fn test_generated_test_fails_against_any_diverging_match() {
+    // `org.example.Photos` declares `Changed` with a different body than the others.
+    #[validate(xml: "./xml/all_matches", signal: "Changed", all_matches: true)]
+    #[derive(Debug, Type)]
+    struct AnyChanged {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    test_AnyChanged_type_signature();
+}
@@ -0,0 +1,154 @@
+// Compile-fail tests for the error paths of `#[validate]`.
+//
+// Each `tests/ui/<name>.rs` must fail to compile with the compiler output in
+// `tests/ui/<name>.stderr`. The fixtures are compiled with `rustc` against the proc-macro
+// built for this test run. Set `LOCKSTEP_UI_BLESS=1` to write the current output to the
+// `.stderr` files instead of comparing.
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// The proc-macro library, as built by cargo for the profile and features of this test run.
+///
+/// Cargo reports the exact artifact of a build, so this asks it rather than guessing among the
+/// libraries in `target`, which may be left over from builds with other features or profiles.
+fn macro_library() -> PathBuf {
+    let mut features = Vec::new();
+    if cfg!(feature = "dtd") {
+        features.push("dtd");
+    }
+    if cfg!(feature = "live-introspection") {
+        features.push("live-introspection");
+    }
+    if cfg!(feature = "gzip") {
+        features.push("gzip");
+    }
+
+    let mut cargo = Command::new(env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo
+        .args(["build", "--lib", "--message-format=json", "--quiet"])
+        .args(["--package", env!("CARGO_PKG_NAME")])
+        .arg("--manifest-path")
+        .arg(Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml"))
+        .args(["--no-default-features", "--features", &features.join(",")]);
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    let output = cargo.output().expect("cargo should run");
+    assert!(
+        output.status.success(),
+        "Failed to build the proc-macro library:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // The `compiler-artifact` message of this crate's proc-macro, among those of its
+    // dependencies.
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let artifact = stdout
+        .lines()
+        .find(|line| {
+            line.contains(r#""reason":"compiler-artifact""#)
+                && line.contains(r#""kind":["proc-macro"]"#)
+                && line.contains(r#""name":"zbus_lockstep_macros""#)
+        })
+        .expect("cargo should report the proc-macro artifact");
+    let filenames = artifact
+        .split_once(r#""filenames":[""#)
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .expect("the artifact should list its files")
+        .0;
+
+    // Undo the JSON escaping of path separators on Windows.
+    PathBuf::from(filenames.replace(r"\\", r"\"))
+}
+
+/// Replace machine-dependent paths in `stderr`.
+fn normalize(stderr: &str, ui_dir: &Path, workspace_dir: &Path) -> String {
+    stderr
+        .replace(&format!("{}/", ui_dir.display()), "$DIR/")
+        .replace(&workspace_dir.display().to_string(), "$WORKSPACE")
+}
+
+#[test]
+fn ui() {
+    let crate_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let workspace_dir = crate_dir
+        .parent()
+        .expect("crate should be in a workspace")
+        .canonicalize()
+        .unwrap();
+    let ui_dir = crate_dir.join("tests/ui");
+
+    let test_exe = env::current_exe().unwrap();
+    let deps_dir = test_exe.parent().unwrap();
+    let library = macro_library();
+    let out_dir = tempfile::tempdir().unwrap();
+
+    let bless = env::var_os("LOCKSTEP_UI_BLESS").is_some();
+
+    let mut fixtures: Vec<PathBuf> = fs::read_dir(&ui_dir)
+        .unwrap()
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension() == Some(OsStr::new("rs")))
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "No fixtures found in tests/ui");
+
+    let mut failures = Vec::new();
+    for fixture in &fixtures {
+        // Relative `xml` paths resolve from the workspace root, as for the other tests.
+        let output = Command::new(env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+            .current_dir(&workspace_dir)
+            .env("CARGO_MANIFEST_DIR", crate_dir)
+            .args([
+                "--edition",
+                "2021",
+                "--crate-type",
+                "lib",
+                "--emit",
+                "metadata",
+            ])
+            .args(["--cap-lints", "allow", "--color", "never"])
+            .arg("--out-dir")
+            .arg(out_dir.path())
+            .arg("--extern")
+            .arg(format!("zbus_lockstep_macros={}", library.display()))
+            .arg("-L")
+            .arg(format!("dependency={}", deps_dir.display()))
+            .arg(fixture)
+            .output()
+            .expect("rustc should run");
+
+        let name = fixture.file_stem().unwrap().to_string_lossy();
+        if output.status.success() {
+            failures.push(format!("{name}: compiled, but should have failed"));
+            continue;
+        }
+
+        let stderr = normalize(
+            &String::from_utf8_lossy(&output.stderr),
+            &ui_dir,
+            &workspace_dir,
+        );
+        let expected_path = fixture.with_extension("stderr");
+
+        if bless {
+            fs::write(&expected_path, &stderr).unwrap();
+            continue;
+        }
+
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if stderr != expected {
+            failures.push(format!(
+                "{name}: unexpected compiler output\n--- expected\n{expected}\n--- found\n{stderr}"
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
@@ -0,0 +1,6 @@
+use zbus_lockstep_macros::validate;
+
+// `Moved` is defined by both `org.example.Left` and `org.example.Right`, with different bodies,
+// so naming the signal does not pick one.
+#[validate(xml: "./xml/duplicates", signal: "Moved")]
+struct Position;
@@ -0,0 +1,5 @@
+use zbus_lockstep_macros::validate;
+
+// `Moved` is defined by both `org.example.Left` and `org.example.Right`.
+#[validate(xml: "./xml/duplicates")]
+struct MovedEvent;
@@ -0,0 +1,19 @@
+use zbus_lockstep_macros::validate;
+
+// The fixtures cannot link `zvariant`. Only the generated test, which this build does not
+// compile, needs the real `Type`, so `#[derive(Type)]` derives `Debug` instead.
+use std::fmt::Debug as Type;
+
+#[validate(xml: "./xml", signal: "RemoveNode", exclusive: true)]
+#[derive(Type)]
+struct RemoveNodeEvent;
+
+mod deletion {
+    use super::Type;
+    use zbus_lockstep_macros::validate;
+
+    // The same signal, claimed from another module.
+    #[validate(xml: "./xml", signal: "RemoveNode", exclusive: true)]
+    #[derive(Type)]
+    struct DeletionEvent;
+}
@@ -0,0 +1,13 @@
+use zbus_lockstep_macros::validate;
+
+// A stand-in for `zvariant::Type`, as in `exclusive_claimed_twice.rs`.
+use std::fmt::Debug as Type;
+
+#[validate(xml: "./xml")]
+#[derive(Type)]
+struct AddNodeEvent;
+
+// Outside of `cfg(test)`, the generated test does not exist.
+pub fn run_validation() {
+    test_AddNodeEvent_type_signature();
+}
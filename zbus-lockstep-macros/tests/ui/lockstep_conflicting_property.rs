@@ -0,0 +1,8 @@
+use zbus_lockstep_macros::Lockstep;
+
+#[derive(Lockstep)]
+#[lockstep(xml = "./xml/conflicting_property")]
+pub struct Settings {
+    #[lockstep(interface = "org.example.Settings", property = "Volume")]
+    pub volume: u32,
+}
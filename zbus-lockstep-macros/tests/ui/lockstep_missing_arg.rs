@@ -0,0 +1,8 @@
+use zbus_lockstep_macros::Lockstep;
+
+#[derive(Lockstep)]
+#[lockstep(xml = "./xml/lockstep_derive")]
+pub struct PlayerStatus {
+    #[lockstep(interface = "org.example.Player", signal = "Seeked", arg = "offset")]
+    pub offset: i64,
+}
@@ -0,0 +1,3 @@
+use zbus_lockstep_macros::validate_from_manifest;
+
+validate_from_manifest!("tests/ui/manifest_validations_without_aggregate.toml");
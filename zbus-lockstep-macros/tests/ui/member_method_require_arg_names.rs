@@ -0,0 +1,9 @@
+use zbus_lockstep_macros::validate;
+
+#[validate(
+    xml: "./xml/member_kinds",
+    interface: "org.example.Control",
+    member: "Refresh",
+    require_arg_names: true
+)]
+struct Refresh(u32);
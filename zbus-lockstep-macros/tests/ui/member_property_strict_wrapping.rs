@@ -0,0 +1,4 @@
+use zbus_lockstep_macros::validate;
+
+#[validate(xml: "./xml/member_kinds", member: "Theme", strict_wrapping: true)]
+struct Theme(String);
@@ -0,0 +1,9 @@
+use zbus_lockstep_macros::validate;
+
+#[validate(
+    xml: "./xml/member_kinds",
+    interface: "org.example.Events",
+    member: "Refresh",
+    direction: "out"
+)]
+struct Refresh(String);
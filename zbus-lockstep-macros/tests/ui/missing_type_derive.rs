@@ -0,0 +1,8 @@
+use zbus_lockstep_macros::validate;
+
+// The type does not derive `Type`, so its signature is unknown.
+#[validate(xml: "./xml", interface: "org.example.Node", signal: "RemoveNode")]
+#[derive(Debug)]
+struct RemoveNodeEvent {
+    name: String,
+}
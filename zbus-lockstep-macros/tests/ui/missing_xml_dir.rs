@@ -0,0 +1,4 @@
+use zbus_lockstep_macros::validate;
+
+#[validate(xml: "./xml/does_not_exist")]
+struct RemoveNodeEvent;
@@ -0,0 +1,4 @@
+use zbus_lockstep_macros::validate;
+
+#[validate(xml: "./xml/single_arg", signal: "Changed", arg: "properties")]
+struct ChangedProperties;
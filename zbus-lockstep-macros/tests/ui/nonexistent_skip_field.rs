@@ -0,0 +1,7 @@
+use zbus_lockstep_macros::validate;
+
+#[validate(xml: "./xml", signal: "RemoveNode", skip_fields: ["cached_at"])]
+struct RemoveNodeEvent {
+    name: String,
+    path: String,
+}
@@ -0,0 +1,5 @@
+use zbus_lockstep_macros::validate;
+
+// `settings.xml` is not well-formed.
+#[validate(xml: "./xml/file_filter", file_filter: "settings.xml")]
+struct SettingsEvent;
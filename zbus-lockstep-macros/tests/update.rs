@@ -0,0 +1,125 @@
+// Tests that with `ZBUS_LOCKSTEP_UPDATE` set, the generated tests report drift instead of failing.
+// The tests re-run themselves in a child process with the variable set, so its output can be
+// inspected and other tests are not affected by it.
+#![allow(unnameable_test_items)]
+
+use std::process::Command;
+
+use zbus_lockstep_macros::validate;
+use zvariant::Type;
+
+// The variables requesting drift to be reported, `LOCKSTEP_UPDATE` being the earlier name.
+const UPDATE_VARS: [&str; 2] = ["ZBUS_LOCKSTEP_UPDATE", "LOCKSTEP_UPDATE"];
+
+/// Whether this process runs with one of [`UPDATE_VARS`] set.
+fn update_requested() -> bool {
+    UPDATE_VARS
+        .iter()
+        .any(|var| std::env::var_os(var).is_some())
+}
+
+/// Runs the test `name` of this binary with `ZBUS_LOCKSTEP_UPDATE` set and returns its standard
+/// error, asserting that it passed.
+fn run_with_update(name: &str) -> String {
+    run_with(name, "ZBUS_LOCKSTEP_UPDATE")
+}
+
+/// Runs the test `name` of this binary with the environment variable `var` set and returns its
+/// standard error, asserting that it passed.
+fn run_with(name: &str, var: &str) -> String {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", name, "--nocapture", "--test-threads=1"])
+        .env(var, "1")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(output.status.success(), "`{name}` failed:\n{stderr}");
+    stderr
+}
+
+#[test]
+fn test_update_reports_drift_from_xml() {
+    #[validate(xml: "./xml", interface: "org.example.Node", signal: "RemoveNode")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct DriftedRemoveNode {
+        name: String,
+        id: u32,
+    }
+
+    if update_requested() {
+        test_DriftedRemoveNode_type_signature();
+        return;
+    }
+
+    let stderr = run_with_update("test_update_reports_drift_from_xml");
+    assert!(
+        stderr.contains(
+            "ZBUS_LOCKSTEP_UPDATE: `DriftedRemoveNode` drifted from \"RemoveNode\" of interface \
+             \"org.example.Node\":\n    struct: \"(su)\"\n    XML:    \"(so)\""
+        ),
+        "unexpected output:\n{stderr}"
+    );
+}
+
+#[test]
+fn test_update_reports_drift_from_signature() {
+    #[validate(signature: "(so)")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct DriftedNode {
+        name: String,
+        id: u32,
+    }
+
+    if update_requested() {
+        test_DriftedNode_type_signature();
+        return;
+    }
+
+    let stderr = run_with_update("test_update_reports_drift_from_signature");
+    assert!(
+        stderr.contains(
+            "ZBUS_LOCKSTEP_UPDATE: `DriftedNode` drifted from its signature:\n    \
+             struct:    \"(su)\"\n    signature: \"(so)\""
+        ),
+        "unexpected output:\n{stderr}"
+    );
+}
+
+#[test]
+fn test_update_with_earlier_name() {
+    #[validate(signature: "(so)")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct DriftedEarlierNode {
+        name: String,
+        id: u32,
+    }
+
+    if update_requested() {
+        test_DriftedEarlierNode_type_signature();
+        return;
+    }
+
+    let stderr = run_with("test_update_with_earlier_name", "LOCKSTEP_UPDATE");
+    assert!(
+        stderr.contains("LOCKSTEP_UPDATE: `DriftedEarlierNode` drifted from its signature:"),
+        "unexpected output:\n{stderr}"
+    );
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_drift_fails_without_update() {
+    #[validate(xml: "./xml", interface: "org.example.Node", signal: "RemoveNode")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct DriftedRemoveNode {
+        name: String,
+        id: u32,
+    }
+
+    test_DriftedRemoveNode_type_signature();
+}
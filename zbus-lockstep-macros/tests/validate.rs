@@ -1,8 +1,9 @@
 // tests/attribute_macro.rs
 #![allow(unnameable_test_items)]
 
+use serde::{Deserialize, Serialize};
 use zbus_lockstep_macros::validate;
-use zvariant::{OwnedObjectPath, Type};
+use zvariant::{OwnedObjectPath, OwnedValue, Type};
 
 #[test]
 fn test_validate_macro_node_add_path_as_env_variable() {
@@ -92,3 +93,1404 @@ fn test_validate_macro_non_matching_signature_of_type() {
     // now call the test generated by the `validate` macro
     test_RemoveNode_type_signature();
 }
+
+#[test]
+fn test_validate_macro_require_serde() {
+    #[validate(xml: "./xml", require_serde: true)]
+    #[derive(Debug, Type, Serialize, Deserialize)]
+    struct AddNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_AddNodeEvent_type_signature();
+}
+
+const NODE_INTERFACE: &str = "org.example.Node";
+
+#[test]
+fn test_validate_macro_interface_const() {
+    #[validate(xml: "./xml", interface_const: NODE_INTERFACE)]
+    #[derive(Debug, Type)]
+    struct RemoveNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_RemoveNodeEvent_type_signature();
+}
+
+const UNKNOWN_INTERFACE: &str = "org.example.Unknown";
+
+#[should_panic]
+#[test]
+fn test_validate_macro_interface_const_not_declaring_signal() {
+    #[validate(xml: "./xml", interface_const: UNKNOWN_INTERFACE)]
+    #[derive(Debug, Type)]
+    struct RemoveNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_RemoveNodeEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_require_arg_names() {
+    #[validate(xml: "./xml", require_arg_names: true)]
+    #[derive(Debug, Type)]
+    struct AlertEvent {
+        _urgent: bool,
+        _color: String,
+        _volume: f64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_AlertEvent_type_signature();
+}
+
+#[should_panic]
+#[test]
+fn test_validate_macro_require_arg_names_with_unnamed_arg() {
+    #[validate(xml: "./xml", require_arg_names: true)]
+    #[derive(Debug, Type)]
+    struct PingEvent {
+        _serial: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_PingEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_file_filter_skips_unrelated_xml() {
+    #[validate(xml: "./xml/file_filter", file_filter: "*.interface.xml")]
+    #[derive(Debug, Type)]
+    struct FilteredEvent {
+        _reason: String,
+        _count: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_FilteredEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_first_match() {
+    #[validate(xml: "./xml/first_match", first_match: true)]
+    #[derive(Debug, Type)]
+    struct DuplicatedEvent {
+        _id: u32,
+        _origin: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_DuplicatedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_exclusive() {
+    #[validate(xml: "./xml", exclusive: true)]
+    #[derive(Debug, Type)]
+    struct AlertEvent {
+        _urgent: bool,
+        _color: String,
+        _volume: f64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_AlertEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_trim_types() {
+    #[validate(xml: "./xml/trim_types", trim_types: true)]
+    #[derive(Debug, Type)]
+    struct SloppyEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+        _flags: Vec<u32>,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_SloppyEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_flat_body_matches_struct() {
+    #[validate(xml: "./xml/wrapping", signal: "Flat")]
+    #[derive(Debug, Type)]
+    struct FlatEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_FlatEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_wrapped_body_matches_struct() {
+    #[validate(xml: "./xml/wrapping", signal: "Wrapped")]
+    #[derive(Debug, Type)]
+    struct WrappedEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_WrappedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_strict_wrapping() {
+    #[validate(xml: "./xml/wrapping", signal: "Wrapped", strict_wrapping: true)]
+    #[derive(Debug, Type)]
+    struct StrictWrappedEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_StrictWrappedEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "With `strict_wrapping`, XML must declare a single struct argument.")]
+fn test_validate_macro_strict_wrapping_rejects_flat_body() {
+    #[validate(xml: "./xml/wrapping", signal: "Flat", strict_wrapping: true)]
+    #[derive(Debug, Type)]
+    struct StrictFlatEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_StrictFlatEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_strict_wrapping_passes_with_skip_fields() {
+    #[validate(
+        xml: "./xml/wrapping",
+        signal: "Wrapped",
+        strict_wrapping: true,
+        skip_fields: ["_cached_at"]
+    )]
+    #[derive(Debug, Type)]
+    struct StrictCachedWrappedEvent {
+        _name: String,
+        _cached_at: u64,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_StrictCachedWrappedEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "`StrictCachedFlatEvent` has the struct signature \"(su)\"")]
+fn test_validate_macro_strict_wrapping_with_skip_fields() {
+    // The message describes the compared fields, without the skipped timestamp.
+    #[validate(
+        xml: "./xml/wrapping",
+        signal: "Flat",
+        strict_wrapping: true,
+        skip_fields: ["_cached_at"]
+    )]
+    #[derive(Debug, Type)]
+    struct StrictCachedFlatEvent {
+        _name: String,
+        _cached_at: u64,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_StrictCachedFlatEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_allow_trailing_metadata() {
+    #[validate(xml: "./xml/trailing_metadata", signal: "Tagged", allow_trailing_metadata: true)]
+    #[derive(Debug, Type)]
+    struct TaggedEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_TaggedEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_validate_macro_allow_trailing_metadata_rejects_other_trailing_args() {
+    #[validate(xml: "./xml/trailing_metadata", signal: "Extended", allow_trailing_metadata: true)]
+    #[derive(Debug, Type)]
+    struct ExtendedEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ExtendedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method_in_args() {
+    #[validate(xml: "./xml/methods", member_type: "method", member: "CreateNode")]
+    #[derive(Debug, Type)]
+    struct CreateNodeRequest {
+        _name: String,
+        _path: OwnedObjectPath,
+        _flags: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CreateNodeRequest_type_signature();
+}
+
+#[test]
+fn test_validate_macro_member_of_any_kind() {
+    // `Refresh` is a signal of `org.example.Events` and a method of `org.example.Control`.
+    #[validate(xml: "./xml/member_kinds", interface: "org.example.Events", member: "Refresh")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct RefreshEvent(String);
+
+    #[validate(xml: "./xml/member_kinds", interface: "org.example.Control", member: "Refresh")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct RefreshRequest(u32);
+
+    // Without an interface, the signal takes precedence over the method.
+    #[validate(xml: "./xml/member_kinds", member: "Refresh")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct AnyRefresh(String);
+
+    #[validate(xml: "./xml/member_kinds", member: "Theme")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Theme(String);
+
+    // now call the tests generated by the `validate` macro
+    test_RefreshEvent_type_signature();
+    test_RefreshRequest_type_signature();
+    test_AnyRefresh_type_signature();
+    test_Theme_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method_in_args_strict_wrapping() {
+    #[validate(
+        xml: "./xml/methods",
+        member_type: "method",
+        member: "CreateNodeFrom",
+        strict_wrapping: true
+    )]
+    #[derive(Debug, Type)]
+    struct CreateNodeFromRequest {
+        _name: String,
+        _path: OwnedObjectPath,
+        _flags: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CreateNodeFromRequest_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "With `strict_wrapping`, XML must declare a single struct argument.")]
+fn test_validate_macro_method_flat_in_args_strict_wrapping() {
+    #[validate(
+        xml: "./xml/methods",
+        member_type: "method",
+        member: "CreateNode",
+        strict_wrapping: true
+    )]
+    #[derive(Debug, Type)]
+    struct StrictCreateNodeRequest {
+        _name: String,
+        _path: OwnedObjectPath,
+        _flags: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_StrictCreateNodeRequest_type_signature();
+}
+
+#[test]
+fn test_validate_macro_ignores_signal_arg_direction() {
+    #[validate(xml: "./xml/signal_direction")]
+    #[derive(Debug, Type)]
+    struct DirectedEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_DirectedEvent_type_signature();
+}
+
+#[cfg(feature = "dtd")]
+#[test]
+fn test_validate_macro_validate_dtd() {
+    #[validate(xml: "./xml", validate_dtd: true)]
+    #[derive(Debug, Type)]
+    struct RemoveNodeDtdEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_RemoveNodeDtdEvent_type_signature();
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_validate_macro_reads_compressed_xml() {
+    #[validate(xml: "./xml/gzip", signal: "Compressed")]
+    #[derive(Debug, Type)]
+    struct CompressedEvent {
+        _name: String,
+        _size: u32,
+        _ratio: f64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CompressedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_allow_value_fields() {
+    #[validate(xml: "./xml", signal: "Alert", allow_value_fields: true)]
+    #[derive(Debug, Type)]
+    struct DynamicAlertEvent {
+        _urgent: bool,
+        _color: OwnedValue,
+        _volume: f64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_DynamicAlertEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_validate_macro_allow_value_fields_checks_other_fields() {
+    #[validate(xml: "./xml", signal: "Alert", allow_value_fields: true)]
+    #[derive(Debug, Type)]
+    struct MistypedAlertEvent {
+        _urgent: bool,
+        _color: OwnedValue,
+        _volume: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_MistypedAlertEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_allow_value_fields_with_extra_trailing() {
+    #[validate(
+        xml: "./xml",
+        signal: "Alert",
+        allow_value_fields: true,
+        allow_extra_trailing: true
+    )]
+    #[derive(Debug, Type)]
+    struct CountedDynamicAlertEvent {
+        _urgent: bool,
+        _color: OwnedValue,
+        _volume: f64,
+        _count: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CountedDynamicAlertEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_double_check() {
+    #[validate(xml: "./xml", signal: "RemoveNode", double_check: true)]
+    #[derive(Debug, Type)]
+    struct CheckedRemoveNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CheckedRemoveNodeEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "XML drifted from snapshot")]
+fn test_validate_macro_double_check_xml_drift() {
+    // The stored snapshot of `Ping` holds "s", the XML declares "u".
+    #[validate(xml: "./xml", signal: "Ping", double_check: true)]
+    #[derive(Debug, Type)]
+    struct CheckedPingEvent {
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CheckedPingEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_validate_macro_double_check_type_drift() {
+    #[validate(xml: "./xml", signal: "RemoveNode", double_check: true)]
+    #[derive(Debug, Type)]
+    struct DriftedRemoveNodeEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_DriftedRemoveNodeEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_signal_signature_annotation() {
+    #[validate(
+        xml: "./xml/signature_annotation",
+        signal: "Changed",
+        signal_signature_annotation: "org.example.BodySignature"
+    )]
+    #[derive(Debug, Type)]
+    struct ChangedEvent {
+        _name: String,
+        _properties: std::collections::HashMap<String, OwnedValue>,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ChangedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_signal_signature_annotation_falls_back_to_args() {
+    #[validate(
+        xml: "./xml/signature_annotation",
+        signal: "Reset",
+        signal_signature_annotation: "org.example.BodySignature"
+    )]
+    #[derive(Debug, Type)]
+    struct ResetEvent {
+        _name: String,
+        _properties: std::collections::HashMap<String, OwnedValue>,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ResetEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method_shorthand() {
+    #[validate(xml: "./xml/methods", method: "CreateNode", direction: "in")]
+    #[derive(Debug, Type)]
+    struct CreateNodeArgs {
+        _name: String,
+        _path: OwnedObjectPath,
+        _flags: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CreateNodeArgs_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method_out_args() {
+    #[validate(xml: "./xml/methods", method: "GetNode", direction: "out")]
+    #[derive(Debug, Type)]
+    struct GetNodeReply {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_GetNodeReply_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method_out_args_as_tuple_struct() {
+    #[validate(xml: "./xml/method_replies", method: "GetPosition", direction: "out")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Position(i32, i32);
+
+    // now call the test generated by the `validate` macro
+    test_Position_type_signature();
+}
+
+#[test]
+fn test_validate_macro_method_single_out_arg() {
+    #[validate(xml: "./xml/method_replies", method: "GetTitle", direction: "out")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Title(String);
+
+    // now call the test generated by the `validate` macro
+    test_Title_type_signature();
+}
+
+#[test]
+fn test_validate_macro_member_method_out_args() {
+    #[validate(xml: "./xml/method_replies", member: "GetPosition", direction: "out")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Position(i32, i32);
+
+    // now call the test generated by the `validate` macro
+    test_Position_type_signature();
+}
+
+#[test]
+fn test_validate_macro_property() {
+    #[validate(xml: "./xml", interface: "org.example.Node", property: "Features")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Features(Vec<String>);
+
+    // now call the test generated by the `validate` macro
+    test_Features_type_signature();
+}
+
+#[test]
+fn test_validate_macro_skips_files_without_extension() {
+    #[validate(xml: "./xml/extensionless")]
+    #[derive(Debug, Type)]
+    struct PlainEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_PlainEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_uppercase_extension() {
+    #[validate(xml: "./xml/uppercase_extension")]
+    #[derive(Debug, Type)]
+    struct LoudEvent {
+        _name: String,
+        _volume: f64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_LoudEvent_type_signature();
+}
+
+// Structs sharing a name in different modules, with the generated tests named apart. These run
+// as `first::first_remove_node_signature` and `second::second_remove_node_signature`.
+mod first {
+    use zbus_lockstep_macros::validate;
+    use zvariant::{OwnedObjectPath, Type};
+
+    #[validate(xml: "./xml", signal: "RemoveNode", test_name: "first_remove_node_signature")]
+    #[derive(Debug, Type)]
+    pub struct RemoveNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+}
+
+mod second {
+    use zbus_lockstep_macros::validate;
+    use zvariant::{OwnedObjectPath, Type};
+
+    #[validate(xml: "./xml", signal: "RemoveNode", test_name: "second_remove_node_signature")]
+    #[derive(Debug, Type)]
+    pub struct RemoveNodeEvent {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+}
+
+#[test]
+fn test_validate_macro_fn_mode() {
+    #[validate(xml: "./xml", signal: "RemoveNode", mode: "fn")]
+    #[derive(Debug, Type)]
+    struct RemoveNodeChecked {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    #[validate(xml: "./xml", signal: "RemoveNode", mode: "fn")]
+    #[derive(Debug, Type)]
+    struct RemoveNodeMismatched {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the functions generated by the `validate` macro
+    assert_eq!(__validate_RemoveNodeChecked(), Ok(()));
+
+    let mismatch = __validate_RemoveNodeMismatched().unwrap_err();
+    assert!(mismatch.starts_with("Type drifted from XML"));
+}
+
+#[test]
+fn test_validate_macro_fn_mode_does_not_panic() {
+    #[validate(xml: "./xml", signal: "RemoveNode", mode: "fn")]
+    #[derive(Debug, Type)]
+    struct RemoveNodeUnwound {
+        _name: String,
+        _id: u32,
+    }
+
+    // Record the threads that panic, passing panics on to the default hook as before.
+    static PANICKED: std::sync::Mutex<Vec<std::thread::ThreadId>> =
+        std::sync::Mutex::new(Vec::new());
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        PANICKED.lock().unwrap().push(std::thread::current().id());
+        default_hook(info);
+    }));
+
+    // now call the function generated by the `validate` macro
+    let mismatch = __validate_RemoveNodeUnwound().unwrap_err();
+    assert!(mismatch.starts_with("Type drifted from XML"));
+
+    let this_thread = std::thread::current().id();
+    assert!(!PANICKED.lock().unwrap().contains(&this_thread));
+}
+
+#[test]
+fn test_validate_macro_on_enum() {
+    // A unit-only enum has the signature "u", as has signal `Ping`.
+    #[validate(xml: "./xml")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    enum PingKind {
+        Echo,
+        Timestamp,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_PingKind_type_signature();
+}
+
+#[test]
+fn test_validate_macro_literal_signature() {
+    #[validate(signature: "(so)")]
+    #[derive(Debug, Type)]
+    struct SpecifiedRemoveNode {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_SpecifiedRemoveNode_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from signature")]
+fn test_validate_macro_literal_signature_mismatch() {
+    #[validate(signature: "(su)")]
+    #[derive(Debug, Type)]
+    struct MisspecifiedRemoveNode {
+        _name: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_MisspecifiedRemoveNode_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "First difference at field 1, `_id`.")]
+fn test_validate_macro_mismatch_names_field() {
+    #[validate(xml: "./xml", signal: "RemoveNode")]
+    #[derive(Debug, Type)]
+    struct RemoveNodeById {
+        _name: String,
+        _id: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_RemoveNodeById_type_signature();
+}
+
+#[test]
+fn test_validate_macro_xml_list() {
+    // `Flat` is defined in the first directory, `Reset` in the second.
+    #[validate(xml: ["./xml/wrapping", "./xml/signature_annotation"])]
+    #[derive(Debug, Type)]
+    struct FlatEvent {
+        _name: String,
+        _id: u32,
+    }
+
+    #[validate(xml: ["./xml/wrapping", "./xml/signature_annotation"], signal: "Reset")]
+    #[derive(Debug, Type)]
+    struct ResetEvent {
+        _name: String,
+        _properties: std::collections::HashMap<String, OwnedValue>,
+    }
+
+    // now call the tests generated by the `validate` macro
+    test_FlatEvent_type_signature();
+    test_ResetEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_identical_duplicates() {
+    // `Synced` is defined identically in two files.
+    #[validate(xml: "./xml/duplicates")]
+    #[derive(Debug, Type)]
+    struct SyncedEvent {
+        _name: String,
+        _revision: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_SyncedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_conflicting_duplicates_need_interface() {
+    // `Moved` has different bodies in `org.example.Left` and `org.example.Right`.
+    #[validate(xml: "./xml/duplicates", interface: "org.example.Right")]
+    #[derive(Debug, Type)]
+    struct MovedEvent {
+        _x: f64,
+        _y: f64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_MovedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_interface_restricts_search() {
+    // Both interfaces define `StateChanged`, with different bodies.
+    #[validate(xml: "./xml/state_changed", interface: "org.example.Player")]
+    #[derive(Debug, Type)]
+    struct PlayerStateChanged {
+        _state: String,
+        _position: i64,
+    }
+
+    #[validate(xml: "./xml/state_changed", interface: "org.example.Recorder")]
+    #[derive(Debug, Type)]
+    struct RecorderStateChanged {
+        _state: String,
+        _recording: bool,
+    }
+
+    // now call the tests generated by the `validate` macro
+    test_PlayerStateChanged_type_signature();
+    test_RecorderStateChanged_type_signature();
+}
+
+#[test]
+fn test_validate_macro_interface_with_prefix() {
+    #[validate(xml: "./xml/state_changed", prefix: "org.example.", interface: "Player")]
+    #[derive(Debug, Type)]
+    struct ShortPlayerStateChanged {
+        _state: String,
+        _position: i64,
+    }
+
+    // The full name still matches.
+    #[validate(
+        xml: "./xml/state_changed",
+        prefix: "org.example.",
+        interface: "org.example.Recorder"
+    )]
+    #[derive(Debug, Type)]
+    struct FullRecorderStateChanged {
+        _state: String,
+        _recording: bool,
+    }
+
+    // now call the tests generated by the `validate` macro
+    test_ShortPlayerStateChanged_type_signature();
+    test_FullRecorderStateChanged_type_signature();
+}
+
+#[test]
+fn test_validate_macro_sole_signal_of_interface() {
+    // `Payload` does not contain "Notified", the only signal of the interface.
+    #[validate(xml: "./xml/sole_signal", interface: "org.example.Notifier")]
+    #[derive(Debug, Type)]
+    struct Payload {
+        _message: String,
+        _urgency: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_Payload_type_signature();
+}
+
+#[test]
+fn test_validate_macro_match_contains() {
+    // Of the signals in `xml/name_match`, only `Machine` is contained in the name.
+    #[validate(xml: "./xml/name_match", match: "contains")]
+    #[derive(Debug, Type)]
+    struct MachineRestarted {
+        _id: u32,
+        _online: bool,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_MachineRestarted_type_signature();
+}
+
+#[test]
+fn test_validate_macro_match_exact() {
+    // The name also contains `State`, which would be ambiguous with "contains".
+    #[validate(xml: "./xml/name_match", match: "exact")]
+    #[derive(Debug, Type)]
+    struct StateChanged {
+        _old: String,
+        _new: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_StateChanged_type_signature();
+}
+
+#[test]
+fn test_validate_macro_match_suffix() {
+    // The name also contains `Machine`, which would be ambiguous with "contains".
+    #[validate(xml: "./xml/name_match", match: "suffix")]
+    #[derive(Debug, Type)]
+    struct MachineState {
+        _state: String,
+        _since: u64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_MachineState_type_signature();
+}
+
+#[test]
+fn test_validate_macro_xml_str() {
+    #[validate(xml_str: r#"
+        <node>
+          <interface name="org.example.Inline">
+            <signal name="Resized">
+              <arg name="width" type="u"/>
+              <arg name="height" type="u"/>
+            </signal>
+          </interface>
+        </node>
+    "#)]
+    #[derive(Debug, Type)]
+    struct ResizedEvent {
+        _width: u32,
+        _height: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ResizedEvent_type_signature();
+}
+
+#[cfg(feature = "live-introspection")]
+#[test]
+#[ignore = "needs a session bus, run with `dbus-run-session`"]
+fn test_validate_macro_live_introspection() {
+    #[validate(
+        bus: "session",
+        service: "org.freedesktop.DBus",
+        path: "/org/freedesktop/DBus",
+        interface: "org.freedesktop.DBus",
+        signal: "NameOwnerChanged",
+    )]
+    #[derive(Debug, Type)]
+    struct NameOwnerChanged {
+        _name: String,
+        _old_owner: String,
+        _new_owner: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_NameOwnerChanged_type_signature();
+}
+
+#[test]
+fn test_validate_macro_arg_by_name() {
+    #[validate(xml: "./xml/single_arg", signal: "Changed", arg: "changed")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct ChangedProperties(std::collections::HashMap<String, zvariant::OwnedValue>);
+
+    // now call the test generated by the `validate` macro
+    test_ChangedProperties_type_signature();
+}
+
+#[test]
+fn test_validate_macro_arg_by_index() {
+    #[validate(xml: "./xml/single_arg", signal: "Changed", arg: 2)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct InvalidatedProperties(Vec<String>);
+
+    // now call the test generated by the `validate` macro
+    test_InvalidatedProperties_type_signature();
+}
+
+#[test]
+fn test_validate_macro_arg_of_method() {
+    #[validate(xml: "./xml/single_arg", method: "Lookup", arg: "flags")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct LookupFlags(u32);
+
+    // now call the test generated by the `validate` macro
+    test_LookupFlags_type_signature();
+}
+
+#[test]
+fn test_validate_macro_arg_of_method_without_direction() {
+    // `key` has no `direction`, which makes it the first in-arg.
+    #[validate(xml: "./xml/default_direction", method: "Set", arg: 0)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct SettingKey(String);
+
+    #[validate(xml: "./xml/default_direction", method: "Set", arg: 1)]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct SettingValue(u32);
+
+    // now call the tests generated by the `validate` macro
+    test_SettingKey_type_signature();
+    test_SettingValue_type_signature();
+}
+
+#[test]
+fn test_validate_macro_explicit_signal_ignores_type_name() {
+    // The name contains all three signals of `xml/name_match`, only `signal` is considered.
+    #[validate(xml: "./xml/name_match", signal: "State")]
+    #[derive(Debug, Type)]
+    struct MachineStateChanged {
+        _state: String,
+        _since: u64,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_MachineStateChanged_type_signature();
+}
+
+#[test]
+fn test_validate_macro_xml_file() {
+    #[validate(xml: "./xml/method_replies/org.example.Window.xml", method: "GetTitle")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct TitleRequest(u32);
+
+    // now call the test generated by the `validate` macro
+    test_TitleRequest_type_signature();
+}
+
+#[test]
+fn test_validate_macro_newtype() {
+    // A newtype has the signature of the value it wraps, `s` rather than `(s)`.
+    #[validate(xml: "./xml/newtype", signal: "NameChanged")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Name(String);
+
+    // now call the test generated by the `validate` macro
+    test_Name_type_signature();
+}
+
+#[test]
+fn test_validate_macro_single_field_struct() {
+    // A struct with one named field stays a struct, to match a body that is a struct.
+    #[validate(xml: "./xml/newtype", signal: "LabelChanged")]
+    #[derive(Debug, Type)]
+    struct Label {
+        _label: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_Label_type_signature();
+}
+
+#[test]
+fn test_validate_macro_on_missing_skip() {
+    #[validate(xml: "./xml/newtype", signal: "Missing", on_missing: "skip")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct MissingEvent {
+        _name: String,
+    }
+
+    // A generated test of the same name would not compile.
+    #[allow(non_snake_case)]
+    fn test_MissingEvent_type_signature() {}
+
+    test_MissingEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_skip_fields() {
+    // `RemoveNode` has the body `so`, the timestamp is only kept by the client.
+    #[validate(xml: "./xml", signal: "RemoveNode", skip_fields: ["_cached_at"])]
+    #[derive(Debug, Type)]
+    struct CachedRemoveNode {
+        _name: String,
+        _cached_at: u64,
+        _path: OwnedObjectPath,
+    }
+
+    #[validate(signature: "(so)", skip_fields: ["_cached_at", "_local_id"])]
+    #[derive(Debug, Type)]
+    struct LocalRemoveNode {
+        _local_id: u32,
+        _name: String,
+        _path: OwnedObjectPath,
+        _cached_at: u64,
+    }
+
+    // now call the tests generated by the `validate` macro
+    test_CachedRemoveNode_type_signature();
+    test_LocalRemoveNode_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "First difference at field 1, `_cached_at`")]
+fn test_validate_macro_skip_fields_compares_kept_fields() {
+    #[validate(xml: "./xml", signal: "RemoveNode", skip_fields: ["_path"])]
+    #[derive(Debug, Type)]
+    struct MisskippedRemoveNode {
+        _name: String,
+        _cached_at: u64,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_MisskippedRemoveNode_type_signature();
+}
+
+#[test]
+fn test_validate_macro_among_annotations_and_docs() {
+    // Members, and their arguments, carry annotations, `doc:` and vendor elements.
+    #[validate(xml: "./xml/annotated")]
+    #[derive(Debug, Type)]
+    struct RenamedEvent {
+        _old: String,
+        _new: String,
+        _path: OwnedObjectPath,
+    }
+
+    #[validate(xml: "./xml/annotated", method: "Rename")]
+    #[derive(Debug, Type)]
+    struct RenameRequest {
+        _path: OwnedObjectPath,
+        _name: String,
+    }
+
+    #[validate(xml: "./xml/annotated", property: "Name")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct Name(String);
+
+    #[validate(
+        xml: "./xml/annotated",
+        signal: "Renamed",
+        signal_signature_annotation: "org.example.Missing"
+    )]
+    #[derive(Debug, Type)]
+    struct AnnotatedRenamedEvent {
+        _old: String,
+        _new: String,
+        _path: OwnedObjectPath,
+    }
+
+    // now call the tests generated by the `validate` macro
+    test_RenamedEvent_type_signature();
+    test_RenameRequest_type_signature();
+    test_Name_type_signature();
+    test_AnnotatedRenamedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_emit_const() {
+    #[validate(xml: "./xml/state_changed", interface: "org.example.Player", emit_const: true)]
+    #[derive(Debug, Type)]
+    struct PlayerStateChanged {
+        _state: String,
+        _position: i64,
+    }
+
+    // Constants of the type's own live alongside the emitted ones.
+    impl PlayerStateChanged {
+        const DEFAULT_STATE: &str = "Stopped";
+    }
+
+    const RECORDER_INTERFACE: &str = "org.example.Recorder";
+
+    #[validate(
+        xml: "./xml/state_changed",
+        interface_const: RECORDER_INTERFACE,
+        emit_const: true
+    )]
+    #[derive(Debug, Type)]
+    struct RecorderStateChanged {
+        _state: String,
+        _recording: bool,
+    }
+
+    assert_eq!(PlayerStateChanged::DBUS_INTERFACE, "org.example.Player");
+    assert_eq!(PlayerStateChanged::DBUS_MEMBER, "StateChanged");
+    assert_eq!(PlayerStateChanged::DEFAULT_STATE, "Stopped");
+    assert_eq!(RecorderStateChanged::DBUS_INTERFACE, "org.example.Recorder");
+    assert_eq!(RecorderStateChanged::DBUS_MEMBER, "StateChanged");
+
+    // now call the tests generated by the `validate` macro
+    test_PlayerStateChanged_type_signature();
+    test_RecorderStateChanged_type_signature();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_validate_macro_file_descriptors() {
+    use zvariant::OwnedFd;
+
+    #[validate(xml: "./xml/fd", signal: "Opened")]
+    #[derive(Debug, Type)]
+    struct OpenedEvent {
+        _id: u32,
+        _fd: OwnedFd,
+    }
+
+    #[validate(xml: "./xml/fd", signal: "BatchOpened")]
+    #[derive(Debug, Type)]
+    struct BatchOpenedEvent {
+        _id: u32,
+        _fds: Vec<OwnedFd>,
+    }
+
+    #[validate(xml: "./xml/fd", signal: "Passed")]
+    #[derive(Debug, Type)]
+    #[allow(dead_code)]
+    struct PassedEvent(OwnedFd);
+
+    // now call the tests generated by the `validate` macro
+    test_OpenedEvent_type_signature();
+    test_BatchOpenedEvent_type_signature();
+    test_PassedEvent_type_signature();
+}
+
+#[cfg(unix)]
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_validate_macro_file_descriptor_is_not_an_integer() {
+    #[validate(xml: "./xml/fd", signal: "Opened")]
+    #[derive(Debug, Type)]
+    struct IndexedOpenedEvent {
+        _id: u32,
+        _fd_index: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_IndexedOpenedEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_xml_glob() {
+    // The pattern selects two of the four files in `xml/glob`.
+    #[validate(xml: "./xml/glob/**/org.freedesktop.*.xml")]
+    #[derive(Debug, Type)]
+    struct StartedEvent {
+        _name: String,
+        _pid: u32,
+    }
+
+    #[validate(xml: "./xml/glob/**/org.freedesktop.*.xml")]
+    #[derive(Debug, Type)]
+    struct StoppedEvent {
+        _code: u32,
+        _reason: String,
+    }
+
+    // now call the tests generated by the `validate` macro
+    test_StartedEvent_type_signature();
+    test_StoppedEvent_type_signature();
+}
+
+// The attribute is emitted verbatim, here the standard test attribute by its full path.
+#[validate(xml: "./xml", signal: "Alert", test_attr: "::core::prelude::v1::test")]
+#[derive(Debug, Type)]
+#[allow(dead_code)]
+struct AttributedAlertEvent {
+    urgent: bool,
+    color: String,
+    volume: f64,
+}
+
+#[test]
+fn test_validate_macro_empty_body() {
+    #[validate(xml: "./xml/empty_body")]
+    #[derive(Debug, Type)]
+    struct ClearedEvent;
+
+    // A struct without fields has the signature "y", but neither has fields to compare.
+    #[validate(xml: "./xml/empty_body")]
+    #[derive(Debug, Type)]
+    struct ResetEvent {}
+
+    // now call the tests generated by the `validate` macro
+    test_ClearedEvent_type_signature();
+    test_ResetEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "but the signal declares no arguments")]
+fn test_validate_macro_empty_body_with_fields() {
+    #[validate(xml: "./xml/empty_body", signal: "Cleared")]
+    #[derive(Debug, Type)]
+    struct ClearedWithReason {
+        _reason: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ClearedWithReason_type_signature();
+}
+
+#[test]
+fn test_validate_macro_any_order() {
+    #[validate(xml: "./xml", signal: "Alert", order: "any")]
+    #[derive(Debug, Type)]
+    struct ReorderedAlertEvent {
+        _volume: f64,
+        _urgent: bool,
+        _color: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ReorderedAlertEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_any_order_with_value_fields() {
+    #[validate(xml: "./xml", signal: "Alert", order: "any", allow_value_fields: true)]
+    #[derive(Debug, Type)]
+    struct ReorderedDynamicAlertEvent {
+        _volume: f64,
+        _color: OwnedValue,
+        _urgent: bool,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ReorderedDynamicAlertEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_validate_macro_strict_order_by_default() {
+    #[validate(xml: "./xml", signal: "Alert")]
+    #[derive(Debug, Type)]
+    struct ReorderedAlertEvent {
+        _volume: f64,
+        _urgent: bool,
+        _color: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ReorderedAlertEvent_type_signature();
+}
+
+#[validate(xml: "./xml", signal: "Alert", print: true)]
+#[derive(Debug, Type)]
+#[allow(dead_code)]
+struct PrintedAlertEvent {
+    urgent: bool,
+    color: String,
+    volume: f64,
+}
+
+#[test]
+fn test_validate_macro_print() {
+    // Run the generated test on its own to capture what it prints.
+    let output = std::process::Command::new(std::env::current_exe().unwrap())
+        .args([
+            "test_PrintedAlertEvent_type_signature",
+            "--exact",
+            "--nocapture",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains(
+        "\"Alert\" of interface \"org.example.Node\" has signature \"(bsd)\", \
+         `PrintedAlertEvent` has signature \"(bsd)\"."
+    ));
+}
+
+#[test]
+fn test_validate_macro_nested_node() {
+    // `Deep` is only defined by an interface in a grandchild node.
+    #[validate(xml: "./xml/nested")]
+    #[derive(Debug, Type)]
+    struct DeepEvent {
+        _depth: u32,
+        _name: String,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_DeepEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_allow_extra_trailing() {
+    #[validate(xml: "./xml", signal: "Alert", allow_extra_trailing: true)]
+    #[derive(Debug, Type)]
+    struct CountedAlertEvent {
+        _urgent: bool,
+        _color: String,
+        _volume: f64,
+        _count: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CountedAlertEvent_type_signature();
+}
+
+#[test]
+fn test_validate_macro_allow_extra_trailing_in_any_order() {
+    #[validate(
+        xml: "./xml",
+        signal: "Alert",
+        allow_extra_trailing: true,
+        order: "any"
+    )]
+    #[derive(Debug, Type)]
+    struct ReorderedCountedAlertEvent {
+        _count: u32,
+        _volume: f64,
+        _color: String,
+        _urgent: bool,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_ReorderedCountedAlertEvent_type_signature();
+}
+
+#[test]
+#[should_panic(expected = "Type drifted from XML")]
+fn test_validate_macro_extra_trailing_not_allowed() {
+    #[validate(xml: "./xml", signal: "Alert")]
+    #[derive(Debug, Type)]
+    struct CountedAlertEvent {
+        _urgent: bool,
+        _color: String,
+        _volume: f64,
+        _count: u32,
+    }
+
+    // now call the test generated by the `validate` macro
+    test_CountedAlertEvent_type_signature();
+}
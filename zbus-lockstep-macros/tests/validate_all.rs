@@ -0,0 +1,45 @@
+// Tests for `validate_all!`, which generates a validation test for each listed type.
+
+use zbus_lockstep_macros::validate_all;
+use zvariant::{OwnedObjectPath, Type};
+
+#[derive(Debug, Type)]
+struct AddNodeEvent {
+    _name: String,
+    _path: OwnedObjectPath,
+}
+
+#[derive(Debug, Type)]
+struct AlertEvent {
+    _urgent: bool,
+    _color: String,
+    _volume: f64,
+}
+
+#[derive(Debug, Type)]
+struct RemoveNodeEvent {
+    _name: String,
+    _path: OwnedObjectPath,
+}
+
+validate_all! {
+    xml: "./xml",
+    AddNodeEvent,
+    AlertEvent,
+    RemoveNodeEvent,
+}
+
+// Arguments apply to every listed type.
+#[derive(Debug, Type)]
+struct FlatEvent {
+    _name: String,
+    _id: u32,
+}
+
+#[derive(Debug, Type)]
+struct WrappedEvent {
+    _name: String,
+    _id: u32,
+}
+
+validate_all! { xml: "./xml/wrapping", interface: "org.example.Wrapping", FlatEvent, WrappedEvent }
@@ -0,0 +1,785 @@
+use zvariant::Signature;
+
+/// Options that relax how [`signatures_are_eq_with`] compares two signatures.
+///
+/// By default, all options are off and signatures must be exactly equal.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct CompareOptions {
+    /// Treat a top-level structure with a single field, e.g. `(v)`, as equal to that field, `v`.
+    ///
+    /// This only unwraps single-field structures. Structures with multiple fields are compared
+    /// as they are.
+    pub flatten_single_field_struct: bool,
+
+    /// Let a variant, `v`, in `rhs` match any signature at the same position in `lhs`.
+    ///
+    /// This is meant for types that deliberately keep a field dynamic, e.g. as
+    /// `zvariant::OwnedValue`, while the XML declares a concrete type. Pass the XML's signature
+    /// as `lhs` and the type's as `rhs`. Applies at any depth, e.g. `a{sv}` matches `a{su}`.
+    pub allow_value_fields: bool,
+
+    /// Compare the top-level fields in any order, as a multiset rather than a sequence.
+    ///
+    /// This is meant for types whose field order is semantically irrelevant, but differs from
+    /// the order of the arguments in XML. Nested structures are still compared in order.
+    /// Combines with `allow_value_fields`, a `v` field in `rhs` then pairs with any one field.
+    pub any_field_order: bool,
+
+    /// Let `rhs` have more top-level fields than `lhs`, if it starts with all those of `lhs`.
+    ///
+    /// This is meant for types that carry fields of their own after those of the XML, e.g. for
+    /// client-side bookkeeping. Pass the XML's signature as `lhs` and the type's as `rhs`.
+    /// Combines with `allow_value_fields`. With `any_field_order`, the fields of `lhs` may be
+    /// anywhere in `rhs`, among any extra ones.
+    pub allow_extra_trailing_fields: bool,
+}
+
+/// Two signatures that were expected to be equal, but are not.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureMismatch {
+    /// The signature that was expected, e.g. from XML.
+    pub expected: Signature,
+    /// The signature that was found, e.g. of a type.
+    pub actual: Signature,
+    /// The first top-level field at which the signatures differ, see [`first_mismatch`].
+    pub first_diff_index: Option<usize>,
+}
+
+impl SignatureMismatch {
+    /// Both signatures on aligned lines, with a caret under the first character at which they
+    /// diverge, see [`signature_caret_diff`].
+    pub fn caret_diff(&self) -> String {
+        signature_caret_diff(&self.expected.to_string(), &self.actual.to_string())
+            .unwrap_or_default()
+    }
+}
+
+impl std::error::Error for SignatureMismatch {}
+
+impl std::fmt::Display for SignatureMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected signature \"{}\", found \"{}\"",
+            self.expected, self.actual
+        )?;
+        if let Some(index) = self.first_diff_index {
+            write!(f, ", first differing at field {index}")?;
+        }
+        write!(f, ".")
+    }
+}
+
+/// Compare `actual` to the `expected` signature, describing how they differ if they do.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::compare_signatures;
+/// use zvariant::Signature;
+///
+/// let expected = Signature::from_str("(so)").unwrap();
+/// let actual = Signature::from_str("(su)").unwrap();
+///
+/// let mismatch = compare_signatures(&expected, &actual).unwrap_err();
+/// assert_eq!(mismatch.first_diff_index, Some(1));
+/// assert!(compare_signatures(&expected, &expected).is_ok());
+/// ```
+pub fn compare_signatures(
+    expected: &Signature,
+    actual: &Signature,
+) -> Result<(), SignatureMismatch> {
+    compare_signatures_with(expected, actual, CompareOptions::default())
+}
+
+/// Compare `actual` to the `expected` signature, relaxed by `options`, describing how they
+/// differ if they do.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::{compare_signatures_with, CompareOptions};
+/// use zvariant::Signature;
+///
+/// let expected = Signature::from_str("(so)").unwrap();
+/// let actual = Signature::from_str("(sv)").unwrap();
+///
+/// let options = CompareOptions {
+///     allow_value_fields: true,
+///     ..Default::default()
+/// };
+///
+/// assert!(compare_signatures_with(&expected, &actual, options).is_ok());
+/// assert!(compare_signatures_with(&expected, &actual, CompareOptions::default()).is_err());
+/// ```
+pub fn compare_signatures_with(
+    expected: &Signature,
+    actual: &Signature,
+    options: CompareOptions,
+) -> Result<(), SignatureMismatch> {
+    if signatures_are_eq_with(expected, actual, options) {
+        return Ok(());
+    }
+
+    Err(SignatureMismatch {
+        expected: expected.clone(),
+        actual: actual.clone(),
+        first_diff_index: first_mismatch(expected, actual),
+    })
+}
+
+/// Compare two signatures for equality.
+///
+/// A variant, `v`, carries its type with each value, so its signature holds no inner type.
+/// A `v` equals any other `v`, whether it comes from XML or from a `Value` or `OwnedValue`,
+/// also inside containers such as the `a{sv}` of a properties map.
+///
+/// Only the wire signature is compared, so owned and borrowed types that serialize alike,
+/// such as `OwnedObjectPath` and `ObjectPath<'_>` or `String` and `Str<'_>`, are equal at any
+/// depth.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::signatures_are_eq;
+/// use zvariant::Signature;
+///
+/// let xml_signature = Signature::from_str("so").unwrap();
+/// let struct_signature = Signature::from_str("(so)").unwrap();
+///
+/// assert!(signatures_are_eq(&xml_signature, &struct_signature));
+/// ```
+pub fn signatures_are_eq(lhs: &Signature, rhs: &Signature) -> bool {
+    signatures_are_eq_with(lhs, rhs, CompareOptions::default())
+}
+
+/// Compare two signatures for equality, relaxed by `options`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::{signatures_are_eq_with, CompareOptions};
+/// use zvariant::Signature;
+///
+/// let wrapped = Signature::from_str("(v)").unwrap();
+/// let bare = Signature::from_str("v").unwrap();
+///
+/// let options = CompareOptions {
+///     flatten_single_field_struct: true,
+///     ..Default::default()
+/// };
+///
+/// assert!(signatures_are_eq_with(&wrapped, &bare, options));
+/// ```
+pub fn signatures_are_eq_with(lhs: &Signature, rhs: &Signature, options: CompareOptions) -> bool {
+    let (lhs, rhs) = if options.flatten_single_field_struct {
+        (
+            flatten_single_field_struct(lhs),
+            flatten_single_field_struct(rhs),
+        )
+    } else {
+        (lhs, rhs)
+    };
+
+    let fields_are_eq = |lhs: &Signature, rhs: &Signature| {
+        if options.allow_value_fields {
+            eq_allowing_value_fields(lhs, rhs)
+        } else {
+            lhs == rhs
+        }
+    };
+
+    if options.any_field_order {
+        return eq_in_any_field_order(lhs, rhs, options.allow_extra_trailing_fields, fields_are_eq);
+    }
+
+    if options.allow_extra_trailing_fields {
+        let lhs = top_level_fields(lhs);
+        let rhs = top_level_fields(rhs);
+
+        return rhs.len() >= lhs.len()
+            && lhs
+                .iter()
+                .zip(rhs.iter())
+                .all(|(lhs, rhs)| fields_are_eq(lhs, rhs));
+    }
+
+    fields_are_eq(lhs, rhs)
+}
+
+/// Find the index of the first top-level field at which two signatures differ.
+///
+/// A structure is compared field by field, any other signature as a single field. Returns
+/// `None` if the signatures are equal. If one signature is a prefix of the other, the index is
+/// that of the first field missing from the shorter one.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::first_mismatch;
+/// use zvariant::Signature;
+///
+/// let xml_signature = Signature::from_str("(sou)").unwrap();
+/// let struct_signature = Signature::from_str("(sox)").unwrap();
+///
+/// assert_eq!(first_mismatch(&xml_signature, &struct_signature), Some(2));
+/// ```
+pub fn first_mismatch(lhs: &Signature, rhs: &Signature) -> Option<usize> {
+    let lhs = top_level_fields(lhs);
+    let rhs = top_level_fields(rhs);
+
+    lhs.iter()
+        .zip(rhs.iter())
+        .position(|(lhs, rhs)| lhs != rhs)
+        .or_else(|| (lhs.len() != rhs.len()).then(|| lhs.len().min(rhs.len())))
+}
+
+/// Render two differing signatures on aligned lines, with a caret under the first character
+/// at which they diverge.
+///
+/// Returns `None` if the signatures are equal. Meant for assertion messages, see
+/// [`assert_eq_signatures!`](crate::assert_eq_signatures).
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::signature_caret_diff;
+///
+/// assert_eq!(
+///     signature_caret_diff("(sa{sv}as)", "(sa{sv}ao)").unwrap(),
+///     "left:  (sa{sv}as)\nright: (sa{sv}ao)\n               ^"
+/// );
+/// assert_eq!(signature_caret_diff("(so)", "(so)"), None);
+/// ```
+pub fn signature_caret_diff(left: &str, right: &str) -> Option<String> {
+    let index = left
+        .chars()
+        .zip(right.chars())
+        .position(|(left, right)| left != right)
+        .or_else(|| {
+            let (left, right) = (left.chars().count(), right.chars().count());
+            (left != right).then(|| left.min(right))
+        })?;
+
+    let indent = " ".repeat("left:  ".len() + index);
+    Some(format!("left:  {left}\nright: {right}\n{indent}^"))
+}
+
+fn top_level_fields(signature: &Signature) -> Vec<&Signature> {
+    match signature {
+        Signature::Structure(fields) => fields.iter().collect(),
+        _ => vec![signature],
+    }
+}
+
+/// Whether each top-level field of `lhs` pairs with a distinct one of `rhs`, per `eq`. Unless
+/// `allow_extra_fields`, every field of `rhs` must be paired as well.
+fn eq_in_any_field_order(
+    lhs: &Signature,
+    rhs: &Signature,
+    allow_extra_fields: bool,
+    eq: impl Fn(&Signature, &Signature) -> bool,
+) -> bool {
+    let lhs = top_level_fields(lhs);
+    let rhs = top_level_fields(rhs);
+    if rhs.len() < lhs.len() || (!allow_extra_fields && rhs.len() != lhs.len()) {
+        return false;
+    }
+
+    // The field of `lhs` each field of `rhs` is paired with, if any.
+    let mut pairs = vec![None; rhs.len()];
+    (0..lhs.len()).all(|field| {
+        pair_field(
+            field,
+            &lhs,
+            &rhs,
+            &eq,
+            &mut pairs,
+            &mut vec![false; rhs.len()],
+        )
+    })
+}
+
+/// Pair the `field` of `lhs` with a field of `rhs` that is not paired yet, or whose pair can be
+/// moved to another field. A `v` may match several fields, so the first fit is not always right.
+fn pair_field(
+    field: usize,
+    lhs: &[&Signature],
+    rhs: &[&Signature],
+    eq: &impl Fn(&Signature, &Signature) -> bool,
+    pairs: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for candidate in 0..rhs.len() {
+        if visited[candidate] || !eq(lhs[field], rhs[candidate]) {
+            continue;
+        }
+        visited[candidate] = true;
+
+        let repaired = match pairs[candidate] {
+            Some(other) => pair_field(other, lhs, rhs, eq, pairs, visited),
+            None => true,
+        };
+        if repaired {
+            pairs[candidate] = Some(field);
+            return true;
+        }
+    }
+
+    false
+}
+
+fn eq_allowing_value_fields(lhs: &Signature, rhs: &Signature) -> bool {
+    match (lhs, rhs) {
+        (_, Signature::Variant) => true,
+        (Signature::Structure(lhs), Signature::Structure(rhs)) => {
+            lhs.len() == rhs.len()
+                && lhs
+                    .iter()
+                    .zip(rhs.iter())
+                    .all(|(lhs, rhs)| eq_allowing_value_fields(lhs, rhs))
+        }
+        (Signature::Array(lhs), Signature::Array(rhs)) => eq_allowing_value_fields(lhs, rhs),
+        (
+            Signature::Dict {
+                key: lhs_key,
+                value: lhs_value,
+            },
+            Signature::Dict {
+                key: rhs_key,
+                value: rhs_value,
+            },
+        ) => {
+            eq_allowing_value_fields(lhs_key, rhs_key)
+                && eq_allowing_value_fields(lhs_value, rhs_value)
+        }
+        _ => lhs == rhs,
+    }
+}
+
+fn flatten_single_field_struct(signature: &Signature) -> &Signature {
+    match signature {
+        Signature::Structure(fields) if fields.len() == 1 => fields
+            .iter()
+            .next()
+            .expect("structure has exactly one field"),
+        _ => signature,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, str::FromStr};
+
+    use zvariant::{ObjectPath, OwnedObjectPath, OwnedValue, Signature, Str, Type, Value};
+
+    use crate::{
+        compare_signatures, first_mismatch, signature_caret_diff, signatures_are_eq,
+        signatures_are_eq_with, CompareOptions, SignatureMismatch,
+    };
+
+    const FLATTEN: CompareOptions = CompareOptions {
+        flatten_single_field_struct: true,
+        allow_value_fields: false,
+        any_field_order: false,
+        allow_extra_trailing_fields: false,
+    };
+
+    const VALUE_FIELDS: CompareOptions = CompareOptions {
+        flatten_single_field_struct: false,
+        allow_value_fields: true,
+        any_field_order: false,
+        allow_extra_trailing_fields: false,
+    };
+
+    const ANY_ORDER: CompareOptions = CompareOptions {
+        flatten_single_field_struct: false,
+        allow_value_fields: false,
+        any_field_order: true,
+        allow_extra_trailing_fields: false,
+    };
+
+    const EXTRA_TRAILING: CompareOptions = CompareOptions {
+        flatten_single_field_struct: false,
+        allow_value_fields: false,
+        any_field_order: false,
+        allow_extra_trailing_fields: true,
+    };
+
+    fn sig(signature: &str) -> Signature {
+        Signature::from_str(signature).expect("Valid signature pattern")
+    }
+
+    #[test]
+    fn test_single_field_struct_differs_by_default() {
+        assert!(!signatures_are_eq(&sig("(v)"), &sig("v")));
+    }
+
+    #[test]
+    fn test_flatten_single_field_struct() {
+        assert!(signatures_are_eq_with(&sig("(v)"), &sig("v"), FLATTEN));
+        assert!(signatures_are_eq_with(&sig("v"), &sig("(v)"), FLATTEN));
+        assert!(signatures_are_eq_with(
+            &sig("(a{sv})"),
+            &sig("a{sv}"),
+            FLATTEN
+        ));
+    }
+
+    #[test]
+    fn test_flatten_single_field_struct_keeps_multi_field_structs() {
+        assert!(!signatures_are_eq_with(&sig("(sv)"), &sig("v"), FLATTEN));
+        assert!(signatures_are_eq_with(&sig("(sv)"), &sig("sv"), FLATTEN));
+    }
+
+    #[test]
+    fn test_flatten_single_field_struct_only_at_top_level() {
+        assert!(!signatures_are_eq_with(&sig("a(v)"), &sig("av"), FLATTEN));
+    }
+
+    #[test]
+    fn test_allow_value_fields() {
+        assert!(signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(sv)"),
+            VALUE_FIELDS
+        ));
+        assert!(signatures_are_eq_with(
+            &sig("(sa{su})"),
+            &sig("(sa{sv})"),
+            VALUE_FIELDS
+        ));
+        assert!(signatures_are_eq_with(
+            &sig("(s(ub))"),
+            &sig("(sv)"),
+            VALUE_FIELDS
+        ));
+    }
+
+    #[test]
+    fn test_allow_value_fields_is_per_position() {
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(vs)"),
+            VALUE_FIELDS
+        ));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(svv)"),
+            VALUE_FIELDS
+        ));
+    }
+
+    #[test]
+    fn test_allow_value_fields_only_on_rhs() {
+        assert!(!signatures_are_eq_with(
+            &sig("(sv)"),
+            &sig("(su)"),
+            VALUE_FIELDS
+        ));
+    }
+
+    #[test]
+    fn test_first_mismatch_finds_diverging_field() {
+        assert_eq!(first_mismatch(&sig("(sou)"), &sig("(sou)")), None);
+        assert_eq!(first_mismatch(&sig("sou"), &sig("(sou)")), None);
+        assert_eq!(first_mismatch(&sig("(sou)"), &sig("(xou)")), Some(0));
+        assert_eq!(first_mismatch(&sig("(sou)"), &sig("(sox)")), Some(2));
+        assert_eq!(first_mismatch(&sig("u"), &sig("s")), Some(0));
+    }
+
+    #[test]
+    fn test_first_mismatch_of_differing_lengths() {
+        assert_eq!(first_mismatch(&sig("(sou)"), &sig("(so)")), Some(2));
+        assert_eq!(first_mismatch(&sig("s"), &sig("(su)")), Some(1));
+    }
+
+    #[test]
+    fn test_any_field_order() {
+        assert!(!signatures_are_eq(&sig("(su)"), &sig("(us)")));
+        assert!(signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(us)"),
+            ANY_ORDER
+        ));
+        assert!(signatures_are_eq_with(
+            &sig("sua{sv}"),
+            &sig("(a{sv}us)"),
+            ANY_ORDER
+        ));
+        assert!(signatures_are_eq_with(&sig("s"), &sig("s"), ANY_ORDER));
+    }
+
+    #[test]
+    fn test_any_field_order_compares_multisets() {
+        assert!(!signatures_are_eq_with(
+            &sig("(ssu)"),
+            &sig("(suu)"),
+            ANY_ORDER
+        ));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(sub)"),
+            ANY_ORDER
+        ));
+        // Nested structures keep their order.
+        assert!(!signatures_are_eq_with(
+            &sig("((su)b)"),
+            &sig("(b(us))"),
+            ANY_ORDER
+        ));
+    }
+
+    #[test]
+    fn test_any_field_order_with_value_fields() {
+        let options = CompareOptions {
+            any_field_order: true,
+            allow_value_fields: true,
+            ..Default::default()
+        };
+        assert!(signatures_are_eq_with(
+            &sig("(bsd)"),
+            &sig("(dvb)"),
+            options
+        ));
+        // The `v` must be left for `u`, although it also matches `s`.
+        assert!(signatures_are_eq_with(&sig("(su)"), &sig("(vs)"), options));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(vv)"),
+            ANY_ORDER
+        ));
+        assert!(!signatures_are_eq_with(&sig("(su)"), &sig("(vb)"), options));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(vsv)"),
+            options
+        ));
+    }
+
+    #[test]
+    fn test_allow_extra_trailing_fields() {
+        assert!(!signatures_are_eq(&sig("(su)"), &sig("(suu)")));
+        assert!(signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(suu)"),
+            EXTRA_TRAILING
+        ));
+        assert!(signatures_are_eq_with(
+            &sig("su"),
+            &sig("(su)"),
+            EXTRA_TRAILING
+        ));
+        assert!(signatures_are_eq_with(
+            &sig("s"),
+            &sig("(sb)"),
+            EXTRA_TRAILING
+        ));
+    }
+
+    #[test]
+    fn test_allow_extra_trailing_fields_only_on_rhs() {
+        assert!(!signatures_are_eq_with(
+            &sig("(suu)"),
+            &sig("(su)"),
+            EXTRA_TRAILING
+        ));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(usu)"),
+            EXTRA_TRAILING
+        ));
+    }
+
+    #[test]
+    fn test_allow_extra_trailing_fields_with_value_fields() {
+        let options = CompareOptions {
+            allow_extra_trailing_fields: true,
+            allow_value_fields: true,
+            ..Default::default()
+        };
+        assert!(signatures_are_eq_with(&sig("(su)"), &sig("(svu)"), options));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(svu)"),
+            EXTRA_TRAILING
+        ));
+    }
+
+    #[test]
+    fn test_allow_extra_trailing_fields_in_any_field_order() {
+        let options = CompareOptions {
+            any_field_order: true,
+            allow_extra_trailing_fields: true,
+            ..Default::default()
+        };
+        assert!(signatures_are_eq_with(&sig("(su)"), &sig("(xus)"), options));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(xus)"),
+            ANY_ORDER
+        ));
+        assert!(!signatures_are_eq_with(
+            &sig("(su)"),
+            &sig("(xss)"),
+            options
+        ));
+        assert!(!signatures_are_eq_with(
+            &sig("(sus)"),
+            &sig("(su)"),
+            options
+        ));
+    }
+
+    #[test]
+    fn test_signature_caret_diff() {
+        assert_eq!(signature_caret_diff("(so)", "(so)"), None);
+        assert_eq!(
+            signature_caret_diff("(so)", "(su)").unwrap(),
+            "left:  (so)\nright: (su)\n         ^"
+        );
+
+        // A signature that is a prefix of the other diverges where it ends.
+        assert_eq!(
+            signature_caret_diff("(so", "(sou)").unwrap(),
+            "left:  (so\nright: (sou)\n          ^"
+        );
+    }
+
+    #[test]
+    fn test_compare_signatures() {
+        assert_eq!(compare_signatures(&sig("(so)"), &sig("so")), Ok(()));
+
+        let mismatch = compare_signatures(&sig("(sou)"), &sig("(sox)")).unwrap_err();
+        assert_eq!(
+            mismatch,
+            SignatureMismatch {
+                expected: sig("(sou)"),
+                actual: sig("(sox)"),
+                first_diff_index: Some(2),
+            }
+        );
+        assert_eq!(
+            mismatch.to_string(),
+            "expected signature \"(sou)\", found \"(sox)\", first differing at field 2."
+        );
+    }
+
+    #[test]
+    fn test_variants_are_eq() {
+        assert!(signatures_are_eq(&sig("v"), Value::SIGNATURE));
+        assert!(signatures_are_eq(&sig("v"), OwnedValue::SIGNATURE));
+        assert!(signatures_are_eq(Value::SIGNATURE, OwnedValue::SIGNATURE));
+        assert!(signatures_are_eq(&sig("av"), <Vec<OwnedValue>>::SIGNATURE));
+        assert!(signatures_are_eq(
+            &sig("(vv)"),
+            <(Value<'_>, OwnedValue)>::SIGNATURE
+        ));
+    }
+
+    #[test]
+    fn test_properties_maps_are_eq() {
+        let properties = sig("a{sv}");
+        assert!(signatures_are_eq(&properties, &sig("a{sv}")));
+        assert!(signatures_are_eq(
+            &properties,
+            <HashMap<String, OwnedValue>>::SIGNATURE
+        ));
+        assert!(signatures_are_eq(
+            &properties,
+            <HashMap<&str, Value<'_>>>::SIGNATURE
+        ));
+
+        // As in `org.freedesktop.DBus.Properties.PropertiesChanged`.
+        assert!(signatures_are_eq(
+            &sig("(sa{sv}as)"),
+            <(String, HashMap<String, OwnedValue>, Vec<String>)>::SIGNATURE
+        ));
+
+        // The relaxed comparisons agree.
+        for options in [FLATTEN, VALUE_FIELDS, ANY_ORDER, EXTRA_TRAILING] {
+            assert!(signatures_are_eq_with(
+                &properties,
+                <HashMap<String, OwnedValue>>::SIGNATURE,
+                options
+            ));
+        }
+    }
+
+    #[test]
+    fn test_owned_and_borrowed_aliases_are_eq() {
+        assert!(signatures_are_eq(
+            ObjectPath::SIGNATURE,
+            OwnedObjectPath::SIGNATURE
+        ));
+        assert!(signatures_are_eq(Str::SIGNATURE, String::SIGNATURE));
+        assert!(signatures_are_eq(<&str>::SIGNATURE, String::SIGNATURE));
+        assert!(signatures_are_eq(<&Signature>::SIGNATURE, &sig("g")));
+    }
+
+    #[test]
+    fn test_nested_owned_and_borrowed_aliases_are_eq() {
+        #[derive(Type)]
+        #[allow(dead_code)]
+        struct Borrowed<'a> {
+            paths: Vec<ObjectPath<'a>>,
+            names: HashMap<Str<'a>, Vec<(ObjectPath<'a>, &'a str)>>,
+            signatures: Vec<&'a Signature>,
+        }
+
+        #[derive(Type)]
+        #[allow(dead_code)]
+        struct Owned {
+            paths: Vec<OwnedObjectPath>,
+            names: HashMap<String, Vec<(OwnedObjectPath, String)>>,
+            signatures: Vec<Signature>,
+        }
+
+        let xml_signature = sig("(aoa{sa(os)}ag)");
+        assert!(signatures_are_eq(&xml_signature, Borrowed::SIGNATURE));
+        assert!(signatures_are_eq(&xml_signature, Owned::SIGNATURE));
+        assert!(signatures_are_eq(Borrowed::SIGNATURE, Owned::SIGNATURE));
+        assert_eq!(
+            compare_signatures(Borrowed::SIGNATURE, Owned::SIGNATURE),
+            Ok(())
+        );
+
+        // Mixing owned and borrowed within one type.
+        assert!(signatures_are_eq(
+            &sig("a{oa(so)}"),
+            <HashMap<OwnedObjectPath, Vec<(Str<'_>, ObjectPath<'_>)>>>::SIGNATURE
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_descriptors_are_eq() {
+        use zvariant::{Fd, OwnedFd};
+
+        assert!(signatures_are_eq(&sig("h"), OwnedFd::SIGNATURE));
+        assert!(signatures_are_eq(&sig("h"), Fd::SIGNATURE));
+        assert!(signatures_are_eq(&sig("uh"), <(u32, OwnedFd)>::SIGNATURE));
+        assert!(signatures_are_eq(
+            &sig("uah"),
+            <(u32, Vec<OwnedFd>)>::SIGNATURE
+        ));
+        assert!(signatures_are_eq(
+            &sig("a{sh}"),
+            <HashMap<String, Fd<'_>>>::SIGNATURE
+        ));
+
+        // An fd is not its index into the message's fds, nor any other integer.
+        assert!(!signatures_are_eq(&sig("uh"), <(u32, u32)>::SIGNATURE));
+        assert!(!signatures_are_eq(&sig("uh"), <(u32, i32)>::SIGNATURE));
+        assert!(!signatures_are_eq(&sig("ah"), <Vec<u32>>::SIGNATURE));
+        assert_eq!(
+            first_mismatch(&sig("(uah)"), <(u32, Vec<i32>)>::SIGNATURE),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use crate::{LockstepError::InvalidIntrospection, Result};
+
+/// The `DBus` introspection DTD, as published with the `DBus` specification.
+pub const INTROSPECTION_DTD: &str = include_str!("introspect.dtd");
+
+/// An element of the introspection DTD.
+struct ElementRule {
+    name: &'static str,
+    children: &'static [&'static str],
+    attributes: &'static [AttributeRule],
+}
+
+/// An attribute of an element of the introspection DTD.
+struct AttributeRule {
+    name: &'static str,
+    required: bool,
+    // Allowed values, any value if empty
+    values: &'static [&'static str],
+}
+
+const fn attribute(name: &'static str, required: bool) -> AttributeRule {
+    AttributeRule {
+        name,
+        required,
+        values: &[],
+    }
+}
+
+// The rules of `INTROSPECTION_DTD`.
+const ELEMENTS: &[ElementRule] = &[
+    ElementRule {
+        name: "node",
+        children: &["node", "interface"],
+        attributes: &[attribute("name", false)],
+    },
+    ElementRule {
+        name: "interface",
+        children: &["method", "signal", "property", "annotation"],
+        attributes: &[attribute("name", true)],
+    },
+    ElementRule {
+        name: "method",
+        children: &["arg", "annotation"],
+        attributes: &[attribute("name", true)],
+    },
+    ElementRule {
+        name: "signal",
+        children: &["arg", "annotation"],
+        attributes: &[attribute("name", true)],
+    },
+    ElementRule {
+        name: "arg",
+        children: &[],
+        attributes: &[
+            attribute("name", false),
+            attribute("type", true),
+            AttributeRule {
+                name: "direction",
+                required: false,
+                values: &["in", "out"],
+            },
+        ],
+    },
+    ElementRule {
+        name: "property",
+        children: &["annotation"],
+        attributes: &[
+            attribute("name", true),
+            attribute("type", true),
+            AttributeRule {
+                name: "access",
+                required: true,
+                values: &["read", "write", "readwrite"],
+            },
+        ],
+    },
+    ElementRule {
+        name: "annotation",
+        children: &[],
+        attributes: &[attribute("name", true), attribute("value", true)],
+    },
+];
+
+/// Check that XML conforms to the `DBus` introspection DTD, [`INTROSPECTION_DTD`].
+///
+/// This checks the structure of the document: which elements may nest in which, and which
+/// attributes each element requires or allows. Errors name the offending element and line,
+/// which is more precise than the parse errors or missing members that malformed XML
+/// otherwise leads to. Signatures in `type` attributes are not checked.
+///
+/// Elements and attributes in a namespace, such as the `<doc:doc>` documentation elements and
+/// their `xmlns:doc` declaration, are not part of the DTD and are ignored, along with everything
+/// inside those elements.
+///
+/// Only available with the `dtd` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::validate_introspection_dtd;
+///
+/// let valid = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <signal name="RemoveNode">
+///     <arg name="path" type="o"/>
+///   </signal>
+/// </interface>
+/// </node>
+/// "#;
+/// assert!(validate_introspection_dtd(valid).is_ok());
+///
+/// let invalid = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <signal name="RemoveNode">
+///     <arg name="path"/>
+///   </signal>
+/// </interface>
+/// </node>
+/// "#;
+/// let err = validate_introspection_dtd(invalid).unwrap_err();
+/// assert_eq!(
+///     err.to_string(),
+///     "Invalid introspection XML: line 5: <arg> is missing required attribute \"type\"."
+/// );
+/// ```
+pub fn validate_introspection_dtd(xml: &str) -> Result<()> {
+    let mut open: Vec<&str> = Vec::new();
+    let mut root_seen = false;
+    let mut rest = xml;
+
+    // The number of innermost open elements that are in a namespace, or inside one that is.
+    let mut foreign_depth = 0_usize;
+
+    while let Some(start) = rest.find('<') {
+        if foreign_depth == 0 {
+            check_text(xml, &rest[..start])?;
+        }
+        let tag_start = &rest[start..];
+        let line = line_of(xml, tag_start);
+        let error = |msg: String| InvalidIntrospection(format!("line {line}: {msg}"));
+
+        // Skip the XML declaration, processing instructions, comments and the doctype.
+        let skip = [("<?", "?>"), ("<!--", "-->"), ("<!DOCTYPE", ">")]
+            .iter()
+            .find(|(open, _)| tag_start.starts_with(open));
+        if let Some((_, close)) = skip {
+            let end = tag_start
+                .find(close)
+                .ok_or_else(|| error("Unterminated markup.".to_string()))?;
+            rest = &tag_start[end + close.len()..];
+            continue;
+        }
+
+        let end = tag_end(tag_start).ok_or_else(|| error("Unterminated tag.".to_string()))?;
+        let tag = &tag_start[1..end];
+        rest = &tag_start[end + 1..];
+
+        // A closing tag must match the innermost open element.
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = name.trim();
+            match open.pop() {
+                Some(expected) if expected == name => {
+                    foreign_depth = foreign_depth.saturating_sub(1);
+                    continue;
+                }
+                Some(expected) => {
+                    return Err(error(format!("Expected </{expected}>, found </{name}>.")).into())
+                }
+                None => return Err(error(format!("Unexpected </{name}>.")).into()),
+            }
+        }
+
+        let (tag, self_closing) = match tag.strip_suffix('/') {
+            Some(tag) => (tag, true),
+            None => (tag, false),
+        };
+        let (name, attributes) = tag
+            .split_once(|c: char| c.is_whitespace())
+            .unwrap_or((tag, ""));
+
+        // Foreign elements may appear anywhere but as root, their contents are not checked.
+        if foreign_depth > 0 || name.contains(':') {
+            if open.is_empty() {
+                return Err(error(format!("<{name}> is not allowed as root element.")).into());
+            }
+            if !self_closing {
+                open.push(name);
+                foreign_depth += 1;
+            }
+            continue;
+        }
+
+        let rule = ELEMENTS
+            .iter()
+            .find(|rule| rule.name == name)
+            .ok_or_else(|| error(format!("Unknown element <{name}>.")))?;
+
+        match open.last() {
+            Some(parent) => {
+                let parent_rule = ELEMENTS
+                    .iter()
+                    .find(|rule| rule.name == *parent)
+                    .expect("open elements have a rule");
+                if !parent_rule.children.contains(&name) {
+                    return Err(error(format!("<{name}> is not allowed in <{parent}>.")).into());
+                }
+            }
+            None if root_seen || name != "node" => {
+                return Err(error(format!("<{name}> is not allowed as root element.")).into());
+            }
+            None => root_seen = true,
+        }
+
+        check_attributes(rule, attributes).map_err(error)?;
+
+        if !self_closing {
+            open.push(rule.name);
+        }
+    }
+
+    check_text(xml, rest)?;
+
+    match open.last() {
+        Some(name) => Err(InvalidIntrospection(format!("<{name}> is not closed.")).into()),
+        None if !root_seen => Err(InvalidIntrospection("No <node> element.".to_string()).into()),
+        None => Ok(()),
+    }
+}
+
+fn check_attributes(rule: &ElementRule, mut attributes: &str) -> std::result::Result<(), String> {
+    let name = rule.name;
+    let mut seen = Vec::new();
+
+    loop {
+        attributes = attributes.trim_start();
+        if attributes.is_empty() {
+            break;
+        }
+
+        let (attribute, value) = attributes
+            .split_once('=')
+            .ok_or_else(|| format!("Malformed attributes in <{name}>."))?;
+        let attribute = attribute.trim();
+        let value = value.trim_start();
+
+        let quote = value
+            .chars()
+            .next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or_else(|| format!("Unquoted value of \"{attribute}\" in <{name}>."))?;
+        let end = value[1..]
+            .find(quote)
+            .ok_or_else(|| format!("Unterminated value of \"{attribute}\" in <{name}>."))?;
+        let value_str = &value[1..=end];
+        attributes = &value[end + 2..];
+
+        // Namespace declarations and attributes in a namespace are not part of the DTD.
+        if attribute == "xmlns" || attribute.contains(':') {
+            continue;
+        }
+
+        let attribute_rule = rule
+            .attributes
+            .iter()
+            .find(|rule| rule.name == attribute)
+            .ok_or_else(|| format!("<{name}> has unknown attribute \"{attribute}\"."))?;
+
+        if !attribute_rule.values.is_empty() && !attribute_rule.values.contains(&value_str) {
+            return Err(format!(
+                "<{name}> has invalid {attribute}=\"{value_str}\", expected one of {:?}.",
+                attribute_rule.values
+            ));
+        }
+
+        seen.push(attribute_rule.name);
+    }
+
+    if let Some(missing) = rule
+        .attributes
+        .iter()
+        .find(|rule| rule.required && !seen.contains(&rule.name))
+    {
+        return Err(format!(
+            "<{name}> is missing required attribute \"{}\".",
+            missing.name
+        ));
+    }
+
+    Ok(())
+}
+
+/// The offset of the `>` that ends the tag `tag_start` starts with, skipping any in quoted
+/// attribute values.
+fn tag_end(tag_start: &str) -> Option<usize> {
+    let mut quote = None;
+
+    for (offset, c) in tag_start.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if open == c => quote = None,
+            (None, '>') => return Some(offset),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Only whitespace may appear between the elements.
+fn check_text(xml: &str, text: &str) -> Result<()> {
+    if let Some(offset) = text.find(|c: char| !c.is_whitespace()) {
+        let line = line_of(xml, &text[offset..]);
+        return Err(InvalidIntrospection(format!("line {line}: Unexpected text.")).into());
+    }
+
+    Ok(())
+}
+
+/// The line of `xml` that `at`, a subslice of it, starts on.
+fn line_of(xml: &str, at: &str) -> usize {
+    let offset = at.as_ptr() as usize - xml.as_ptr() as usize;
+    xml[..offset].matches('\n').count() + 1
+}
+
+#[cfg(test)]
+mod test {
+    use crate::validate_introspection_dtd;
+
+    #[test]
+    fn test_fixtures_conform_to_dtd() {
+        let xml = std::fs::read_to_string("../xml/test_definition_file.xml").unwrap();
+        validate_introspection_dtd(&xml).unwrap();
+    }
+
+    #[test]
+    fn test_misplaced_element() {
+        let xml = r#"<node><signal name="Orphan"/></node>"#;
+        let err = validate_introspection_dtd(xml).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid introspection XML: line 1: <signal> is not allowed in <node>."
+        );
+    }
+
+    #[test]
+    fn test_invalid_attribute_value() {
+        let xml = r#"<node>
+            <interface name="org.example.Node">
+              <property name="Features" type="as" access="readonly"/>
+            </interface>
+          </node>"#;
+        let err = validate_introspection_dtd(xml).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Invalid introspection XML: line 3: <property> has invalid access="));
+    }
+
+    #[test]
+    fn test_angle_bracket_in_attribute_value() {
+        let xml = r#"<node>
+            <interface name="org.example.Node">
+              <annotation name="org.example.Condition" value="count > 0 && count < 10"/>
+              <signal name="Moved">
+                <arg name="offset" type="i"/>
+              </signal>
+            </interface>
+          </node>"#;
+        validate_introspection_dtd(xml).unwrap();
+    }
+
+    #[test]
+    fn test_namespaced_elements_and_attributes_are_ignored() {
+        let xml = r#"<node xmlns:doc="http://www.freedesktop.org/dbus/1.0/doc.dtd">
+            <interface name="org.example.Node">
+              <doc:doc>
+                <doc:description><doc:para>Nodes, <doc:ref>moved</doc:ref>.</doc:para></doc:description>
+              </doc:doc>
+              <signal name="Moved" doc:since="1.2">
+                <doc:doc><doc:summary>Emitted when a node moves.</doc:summary></doc:doc>
+                <arg name="offset" type="i">
+                  <doc:doc><doc:summary>By how much > 0.</doc:summary></doc:doc>
+                </arg>
+              </signal>
+            </interface>
+          </node>"#;
+        validate_introspection_dtd(xml).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_element_inside_namespaced_element_is_ignored() {
+        let xml = r#"<node>
+            <interface name="org.example.Node">
+              <doc:doc><signal>Not a signal.</signal></doc:doc>
+            </interface>
+          </node>"#;
+        validate_introspection_dtd(xml).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_attribute_without_namespace() {
+        let xml = r#"<node><interface name="org.example.Node" since="1.2"/></node>"#;
+        let err = validate_introspection_dtd(xml).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid introspection XML: line 1: <interface> has unknown attribute \"since\"."
+        );
+    }
+
+    #[test]
+    fn test_unclosed_element() {
+        let xml = r#"<node><interface name="org.example.Node"></node>"#;
+        let err = validate_introspection_dtd(xml).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid introspection XML: line 1: Expected </interface>, found </node>."
+        );
+    }
+}
@@ -3,8 +3,22 @@
 pub enum LockstepError {
     ArgumentNotFound(String),
     InterfaceNotFound(String),
+    IntrospectionFailed {
+        destination: String,
+        path: String,
+        // The D-Bus error name, if the bus or the service replied with an error
+        error_name: Option<String>,
+        message: String,
+    },
+    InvalidIntrospection(String),
     MemberNotFound(String),
     PropertyNotFound(String),
+    SnapshotMismatch {
+        snapshot: String,
+        expected: String,
+        found: String,
+    },
+    TypeNotRegistered(String),
 }
 
 impl std::error::Error for LockstepError {}
@@ -18,12 +32,40 @@ impl std::fmt::Display for LockstepError {
             LockstepError::InterfaceNotFound(name) => {
                 write!(f, "Interface \"{name}\" not found.")
             }
+            LockstepError::IntrospectionFailed {
+                destination,
+                path,
+                error_name,
+                message,
+            } => {
+                write!(f, "Failed to introspect \"{path}\" of \"{destination}\": ")?;
+                if let Some(error_name) = error_name {
+                    write!(f, "{error_name}: ")?;
+                }
+                write!(f, "{message}")
+            }
+            LockstepError::InvalidIntrospection(msg) => {
+                write!(f, "Invalid introspection XML: {msg}")
+            }
             LockstepError::MemberNotFound(name) => {
                 write!(f, "Member \"{name}\" not found.")
             }
             LockstepError::PropertyNotFound(name) => {
                 write!(f, "Property \"{name}\" not found.")
             }
+            LockstepError::SnapshotMismatch {
+                snapshot,
+                expected,
+                found,
+            } => {
+                write!(
+                    f,
+                    "Snapshot \"{snapshot}\" holds signature \"{expected}\", but found \"{found}\"."
+                )
+            }
+            LockstepError::TypeNotRegistered(name) => {
+                write!(f, "Type \"{name}\" not registered.")
+            }
         }
     }
 }
@@ -0,0 +1,417 @@
+//! Decompression of gzip files, RFC 1952, holding DEFLATE data, RFC 1951.
+//!
+//! This is a small, unoptimized inflater, enough to read compressed introspection XML without
+//! a dependency.
+
+/// The magic number, compression method and flags of a gzip member header.
+const MAGIC: [u8; 2] = [0x1f, 0x8b];
+const DEFLATE: u8 = 8;
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+/// Base lengths and extra bits of the length symbols 257 to 285.
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+/// Base distances and extra bits of the distance symbols 0 to 29.
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The order in which the code lengths of the code length alphabet are stored.
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompress `data`, one or more gzip members, verifying the checksum and size of each.
+pub(crate) fn decompress(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut rest = data;
+
+    while !rest.is_empty() {
+        let start = out.len();
+        let body = skip_header(rest)?;
+
+        let mut bits = Bits::new(body);
+        inflate(&mut bits, &mut out)?;
+        let trailer = &body[bits.bytes_read()..];
+        if trailer.len() < 8 {
+            return Err("truncated gzip trailer".to_owned());
+        }
+
+        let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+        if crc32(&out[start..]) != crc {
+            return Err("gzip checksum mismatch".to_owned());
+        }
+        // The size is stored modulo 2^32.
+        if (out.len() - start) as u32 != size {
+            return Err("gzip size mismatch".to_owned());
+        }
+
+        rest = &trailer[8..];
+    }
+
+    Ok(out)
+}
+
+/// The data following the gzip member header at the start of `data`.
+fn skip_header(data: &[u8]) -> Result<&[u8], String> {
+    let truncated = || "truncated gzip header".to_owned();
+
+    if data.len() < 10 || data[..2] != MAGIC {
+        return Err("not a gzip file".to_owned());
+    }
+    if data[2] != DEFLATE {
+        return Err(format!("unsupported gzip compression method {}", data[2]));
+    }
+    let flags = data[3];
+    let mut rest = &data[10..];
+
+    if flags & FEXTRA != 0 {
+        let len = rest.get(..2).ok_or_else(truncated)?;
+        let len = usize::from(u16::from_le_bytes([len[0], len[1]]));
+        rest = rest.get(2 + len..).ok_or_else(truncated)?;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            let end = rest.iter().position(|&b| b == 0).ok_or_else(truncated)?;
+            rest = &rest[end + 1..];
+        }
+    }
+    if flags & FHCRC != 0 {
+        rest = rest.get(2..).ok_or_else(truncated)?;
+    }
+
+    Ok(rest)
+}
+
+/// Reads the bits of a byte slice, least significant bit first, as DEFLATE stores them.
+struct Bits<'a> {
+    data: &'a [u8],
+    // The position of the next bit.
+    pos: usize,
+}
+
+impl<'a> Bits<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Bits { data, pos: 0 }
+    }
+
+    /// Read `count` bits, at most 16, as a number whose first bit is the least significant.
+    fn read(&mut self, count: u32) -> Result<u32, String> {
+        let mut value = 0;
+        for i in 0..count {
+            let byte = self
+                .data
+                .get(self.pos / 8)
+                .ok_or_else(|| "truncated DEFLATE data".to_owned())?;
+            value |= u32::from((byte >> (self.pos % 8)) & 1) << i;
+            self.pos += 1;
+        }
+
+        Ok(value)
+    }
+
+    /// Skip to the start of the next byte.
+    fn align(&mut self) {
+        self.pos = self.pos.div_ceil(8) * 8;
+    }
+
+    /// The number of bytes read so far, including a partially read byte.
+    fn bytes_read(&self) -> usize {
+        self.pos.div_ceil(8)
+    }
+}
+
+/// A canonical Huffman code, as the number of codes of each length and the symbols in order of
+/// their codes.
+struct Huffman {
+    counts: [u16; 16],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    /// The code with the code length of each symbol, where 0 means the symbol is unused.
+    fn new(lengths: &[u8]) -> Result<Self, String> {
+        let mut counts = [0_u16; 16];
+        for &length in lengths {
+            counts[usize::from(length)] += 1;
+        }
+        counts[0] = 0;
+
+        // Reject over-subscribed codes. Incomplete codes are allowed, as for a single
+        // distance code.
+        let mut left = 1_i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - i32::from(count);
+            if left < 0 {
+                return Err("invalid Huffman code".to_owned());
+            }
+        }
+
+        let mut offsets = [0_u16; 16];
+        for length in 1..15 {
+            offsets[length + 1] = offsets[length] + counts[length];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &length) in lengths.iter().enumerate() {
+            if length != 0 {
+                let offset = &mut offsets[usize::from(length)];
+                symbols[usize::from(*offset)] = symbol as u16;
+                *offset += 1;
+            }
+        }
+
+        Ok(Huffman { counts, symbols })
+    }
+
+    /// Decode the next symbol from `bits`.
+    fn decode(&self, bits: &mut Bits<'_>) -> Result<u16, String> {
+        // The first code of the current length, and the index of its symbol.
+        let (mut code, mut first, mut index) = (0_i32, 0_i32, 0_i32);
+        for &count in &self.counts[1..] {
+            code |= bits.read(1)? as i32;
+            let count = i32::from(count);
+            if code - first < count {
+                return Ok(self.symbols[(index + code - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err("invalid Huffman code".to_owned())
+    }
+}
+
+/// Inflate the DEFLATE stream of `bits` into `out`, up to and including its final block.
+fn inflate(bits: &mut Bits<'_>, out: &mut Vec<u8>) -> Result<(), String> {
+    let start = out.len();
+
+    loop {
+        let last = bits.read(1)? == 1;
+        match bits.read(2)? {
+            0 => stored(bits, out)?,
+            1 => {
+                let (lengths, distances) = fixed_codes()?;
+                codes(bits, out, start, &lengths, &distances)?;
+            }
+            2 => {
+                let (lengths, distances) = dynamic_codes(bits)?;
+                codes(bits, out, start, &lengths, &distances)?;
+            }
+            _ => return Err("invalid DEFLATE block type".to_owned()),
+        }
+
+        if last {
+            return Ok(());
+        }
+    }
+}
+
+/// Copy a stored, uncompressed block.
+fn stored(bits: &mut Bits<'_>, out: &mut Vec<u8>) -> Result<(), String> {
+    bits.align();
+    let pos = bits.pos / 8;
+    let header = bits
+        .data
+        .get(pos..pos + 4)
+        .ok_or_else(|| "truncated DEFLATE data".to_owned())?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    let nlen = u16::from_le_bytes([header[2], header[3]]);
+    if len != !nlen {
+        return Err("invalid stored block length".to_owned());
+    }
+
+    let block = bits
+        .data
+        .get(pos + 4..pos + 4 + usize::from(len))
+        .ok_or_else(|| "truncated DEFLATE data".to_owned())?;
+    out.extend_from_slice(block);
+    bits.pos = (pos + 4 + usize::from(len)) * 8;
+
+    Ok(())
+}
+
+/// The fixed literal/length and distance codes.
+fn fixed_codes() -> Result<(Huffman, Huffman), String> {
+    let mut lengths = [0_u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    Ok((Huffman::new(&lengths)?, Huffman::new(&[5; 30])?))
+}
+
+/// Read the literal/length and distance codes of a dynamic block.
+fn dynamic_codes(bits: &mut Bits<'_>) -> Result<(Huffman, Huffman), String> {
+    let literals = bits.read(5)? as usize + 257;
+    let distances = bits.read(5)? as usize + 1;
+    let code_lengths = bits.read(4)? as usize + 4;
+
+    let mut lengths = [0_u8; 19];
+    for &symbol in &CODE_LENGTH_ORDER[..code_lengths] {
+        lengths[symbol] = bits.read(3)? as u8;
+    }
+    let code_length_code = Huffman::new(&lengths)?;
+
+    // The code lengths of both codes, as one sequence, with runs of repeated lengths.
+    let mut lengths = Vec::with_capacity(literals + distances);
+    while lengths.len() < literals + distances {
+        let (length, repeat) = match code_length_code.decode(bits)? {
+            length @ 0..=15 => (length as u8, 1),
+            16 => {
+                let previous = *lengths
+                    .last()
+                    .ok_or_else(|| "repeated code length without a previous one".to_owned())?;
+                (previous, 3 + bits.read(2)?)
+            }
+            17 => (0, 3 + bits.read(3)?),
+            _ => (0, 11 + bits.read(7)?),
+        };
+        lengths.extend(std::iter::repeat_n(length, repeat as usize));
+    }
+    if lengths.len() != literals + distances {
+        return Err("too many code lengths".to_owned());
+    }
+    if lengths[256] == 0 {
+        return Err("missing end-of-block code".to_owned());
+    }
+
+    Ok((
+        Huffman::new(&lengths[..literals])?,
+        Huffman::new(&lengths[literals..])?,
+    ))
+}
+
+/// Decode the literals and back-references of a compressed block, up to its end.
+///
+/// Back-references may reach into earlier blocks of the same stream, which starts at `start`
+/// in `out`.
+fn codes(
+    bits: &mut Bits<'_>,
+    out: &mut Vec<u8>,
+    start: usize,
+    lengths: &Huffman,
+    distances: &Huffman,
+) -> Result<(), String> {
+    loop {
+        let symbol = usize::from(lengths.decode(bits)?);
+        match symbol {
+            0..=255 => out.push(symbol as u8),
+            256 => return Ok(()),
+            257..=285 => {
+                let index = symbol - 257;
+                let length = usize::from(LENGTH_BASE[index])
+                    + bits.read(u32::from(LENGTH_EXTRA[index]))? as usize;
+
+                let index = usize::from(distances.decode(bits)?);
+                if index >= DISTANCE_BASE.len() {
+                    return Err("invalid DEFLATE distance".to_owned());
+                }
+                let distance = usize::from(DISTANCE_BASE[index])
+                    + bits.read(u32::from(DISTANCE_EXTRA[index]))? as usize;
+                if distance > out.len() - start {
+                    return Err("DEFLATE distance too far back".to_owned());
+                }
+
+                // The copy may overlap what it produces, so go byte by byte.
+                for _ in 0..length {
+                    out.push(out[out.len() - distance]);
+                }
+            }
+            _ => return Err("invalid DEFLATE length".to_owned()),
+        }
+    }
+}
+
+/// The CRC-32 of `data`, as gzip stores it.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod test {
+    use super::{crc32, decompress};
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_decompress_members_of_each_block_type() {
+        for (name, data) in [
+            ("stored", &STORED[..]),
+            ("fixed", &FIXED[..]),
+            ("two members", &TWO_MEMBERS[..]),
+        ] {
+            let out = decompress(data).unwrap_or_else(|e| panic!("{name}: {e}"));
+            assert_eq!(out, EXPECTED, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_decompress_dynamic_block() {
+        let data = std::fs::read("../xml/gzip/org.example.Compressed.xml.gz").unwrap();
+        let xml = String::from_utf8(decompress(&data).unwrap()).unwrap();
+        assert!(xml.contains(r#"<signal name="Compressed">"#));
+    }
+
+    #[test]
+    fn test_decompress_rejects_corrupt_data() {
+        let mut corrupt = FIXED.to_vec();
+        let crc = corrupt.len() - 8;
+        corrupt[crc] ^= 1;
+        assert_eq!(decompress(&corrupt).unwrap_err(), "gzip checksum mismatch");
+
+        assert_eq!(decompress(b"<node/>").unwrap_err(), "not a gzip file");
+        assert!(decompress(&FIXED[..FIXED.len() - 4]).is_err());
+    }
+
+    // `<node><node/></node>` compressed by Python's `gzip.compress` at level 0 and 9, the
+    // latter also in two members.
+    const EXPECTED: &[u8] = b"<node><node/></node>";
+    const STORED: [u8; 43] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x04, 0x03, 0x01, 0x14, 0x00, 0xeb, 0xff,
+        0x3c, 0x6e, 0x6f, 0x64, 0x65, 0x3e, 0x3c, 0x6e, 0x6f, 0x64, 0x65, 0x2f, 0x3e, 0x3c, 0x2f,
+        0x6e, 0x6f, 0x64, 0x65, 0x3e, 0x9a, 0x45, 0xca, 0xd5, 0x14, 0x00, 0x00, 0x00,
+    ];
+    const FIXED: [u8; 34] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xb3, 0xc9, 0xcb, 0x4f, 0x49,
+        0xb5, 0xb3, 0x01, 0x91, 0xfa, 0x76, 0x36, 0xfa, 0x60, 0x1e, 0x00, 0x9a, 0x45, 0xca, 0xd5,
+        0x14, 0x00, 0x00, 0x00,
+    ];
+    const TWO_MEMBERS: [u8; 60] = [
+        0x1f, 0x8b, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xb3, 0xc9, 0xcb, 0x4f, 0x49,
+        0xb5, 0xb3, 0xc9, 0x03, 0x00, 0xea, 0x38, 0x2d, 0x02, 0x08, 0x00, 0x00, 0x00, 0x1f, 0x8b,
+        0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0xcb, 0x4f, 0x49, 0xd5, 0xb7, 0xb3, 0xd1,
+        0xcf, 0xcb, 0x4f, 0x49, 0xb5, 0x03, 0x00, 0xac, 0xa4, 0x94, 0x71, 0x0c, 0x00, 0x00, 0x00,
+    ];
+}
@@ -16,23 +16,51 @@
 //! The macros assume that the file path to the XML files is either:
 //!
 //! - `xml` or `XML`, the default path for `DBus` XML files - or is set by the
-//! - `LOCKSTEP_XML_PATH`, the env variable that overrides the default.
+//! - `ZBUS_LOCKSTEP_XML_PATH`, the env variable that overrides the default. Its earlier
+//!   name, `LOCKSTEP_XML_PATH`, is read if it is not set.
 #![doc(html_root_url = "https://docs.rs/zbus-lockstep/0.5.0")]
 #![allow(clippy::missing_errors_doc)]
 
+mod compare;
+#[cfg(feature = "dtd")]
+mod dtd;
 mod error;
+#[cfg(feature = "gzip")]
+mod gzip;
+#[cfg(feature = "live-introspection")]
+mod live;
 mod macros;
+mod registry;
+mod snapshot;
+mod xml_dir;
 
 use std::{io::Read, str::FromStr};
 
+pub use compare::{
+    compare_signatures, compare_signatures_with, first_mismatch, signature_caret_diff,
+    signatures_are_eq, signatures_are_eq_with, CompareOptions, SignatureMismatch,
+};
+#[cfg(feature = "dtd")]
+pub use dtd::{validate_introspection_dtd, INTROSPECTION_DTD};
 pub use error::LockstepError;
-pub use macros::resolve_xml_path;
+#[cfg(feature = "live-introspection")]
+pub use live::{introspect, Bus};
+pub use macros::{assert_validations, resolve_xml_path, resolve_xml_paths, Validation};
+pub use registry::{register_type_signature, registered_type_signature, signature_from_type_names};
+pub use snapshot::check_signature_snapshot;
+pub use xml_dir::{
+    find_interface, find_matching_signals, find_xml_files, list_signals, load_xml_dir,
+    read_xml_file, read_xml_files, validate_signal_signature, validate_signal_type_names,
+    InterfaceEntry,
+};
 pub use zbus_xml::{
     self,
     ArgDirection::{In, Out},
     Node,
 };
-use zvariant::Signature;
+#[doc(hidden)]
+pub use zvariant;
+use zvariant::{Signature, Type};
 use LockstepError::{ArgumentNotFound, InterfaceNotFound, MemberNotFound, PropertyNotFound};
 
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
@@ -44,11 +72,98 @@ pub enum MsgType {
     Property,
 }
 
+/// Collect the interfaces of `node` and of its child nodes, at any depth, in document order.
+///
+/// Introspection XML may describe a hierarchy of objects, with the interfaces of each in its
+/// own `<node>`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{all_interfaces, Node};
+///
+/// let xml = r#"
+/// <node>
+///   <interface name="org.example.Root"/>
+///   <node name="child">
+///     <interface name="org.example.Child"/>
+///   </node>
+/// </node>
+/// "#;
+///
+/// let node = Node::from_reader(xml.as_bytes()).unwrap();
+/// let names: Vec<_> = all_interfaces(&node)
+///     .iter()
+///     .map(|interface| interface.name().to_string())
+///     .collect();
+///
+/// assert_eq!(names, ["org.example.Root", "org.example.Child"]);
+/// ```
+pub fn all_interfaces<'n, 'a>(node: &'n Node<'a>) -> Vec<&'n zbus_xml::Interface<'a>> {
+    let mut interfaces: Vec<_> = node.interfaces().iter().collect();
+    for child in node.nodes() {
+        interfaces.extend(all_interfaces(child));
+    }
+
+    interfaces
+}
+
+/// Parse `xml` and find the signal named `member_name` of the interface `interface_name`.
+fn find_signal(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<zbus_xml::Signal<'static>> {
+    let node = Node::from_reader(xml)?;
+
+    let interfaces = all_interfaces(&node);
+    let interface = interfaces
+        .iter()
+        .find(|iface| iface.name() == interface_name)
+        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
+
+    let signal = interface
+        .signals()
+        .iter()
+        .find(|signal| signal.name() == member_name)
+        .cloned()
+        .ok_or(MemberNotFound(member_name.to_owned()))?;
+
+    Ok(signal)
+}
+
+/// Parse `xml` and find the method named `member_name` of the interface `interface_name`.
+fn find_method(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<zbus_xml::Method<'static>> {
+    let node = Node::from_reader(xml)?;
+
+    let interfaces = all_interfaces(&node);
+    let interface = interfaces
+        .iter()
+        .find(|iface| iface.name() == interface_name)
+        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
+
+    let method = interface
+        .methods()
+        .iter()
+        .find(|method| method.name() == member_name)
+        .cloned()
+        .ok_or(MemberNotFound(member_name.to_owned()))?;
+
+    Ok(method)
+}
+
 /// Retrieve a signal's body type signature from `DBus` XML.
 ///
 /// If you provide an argument name, then the signature of that argument is returned.
 /// If you do not provide an argument name, then the signature of all arguments is returned.    
 ///
+/// Signal arguments have no direction. A `direction` attribute on a signal argument, which
+/// some generators emit regardless, is ignored.
+///
 /// # Examples
 ///
 /// ```rust
@@ -86,24 +201,12 @@ pub enum MsgType {
 /// assert_eq!(&signature, DeviceEvent::SIGNATURE);
 /// ```
 pub fn get_signal_body_type(
-    mut xml: impl Read,
+    xml: impl Read,
     interface_name: &str,
     member_name: &str,
     arg: Option<&str>,
 ) -> Result<Signature> {
-    let node = Node::from_reader(&mut xml)?;
-
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
-
-    let signals = interface.signals();
-    let signal = signals
-        .iter()
-        .find(|signal| signal.name() == member_name)
-        .ok_or(MemberNotFound(member_name.to_owned()))?;
+    let signal = find_signal(xml, interface_name, member_name)?;
 
     let signature = {
         if let Some(arg_name) = arg {
@@ -124,6 +227,267 @@ pub fn get_signal_body_type(
     Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
 }
 
+/// Retrieve the positions of a signal's arguments that lack a `name` attribute.
+///
+/// Argument names are optional in `DBus` XML, but documented descriptions name all of them.
+/// This returns the zero-based positions of the unnamed arguments, which is empty if all
+/// arguments are named.
+///
+/// # Examples
+///
+/// ```rust
+/// # use std::io::{Seek, SeekFrom, Write};
+/// # use tempfile::tempfile;
+/// use zbus_lockstep::get_signal_unnamed_args;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <signal name="Alert">
+///     <arg name="urgent" type="b"/>
+///     <arg type="s"/>
+///   </signal>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let mut xml_file = tempfile().unwrap();
+/// xml_file.write_all(xml.as_bytes()).unwrap();
+/// xml_file.seek(SeekFrom::Start(0)).unwrap();
+///
+/// let unnamed = get_signal_unnamed_args(xml_file, "org.example.Node", "Alert").unwrap();
+/// assert_eq!(unnamed, vec![1]);
+/// ```
+pub fn get_signal_unnamed_args(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<Vec<usize>> {
+    let signal = find_signal(xml, interface_name, member_name)?;
+
+    Ok(signal
+        .args()
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| arg.name().is_none())
+        .map(|(position, _)| position)
+        .collect())
+}
+
+/// Retrieve the value of an annotation on a signal from XML.
+///
+/// Returns `None` if the signal carries no annotation named `annotation_name`.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::get_signal_annotation;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <signal name="Changed">
+///     <annotation name="org.example.BodySignature" value="(sa{sv})"/>
+///     <arg name="properties" type="v"/>
+///   </signal>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let value = get_signal_annotation(
+///     xml.as_bytes(),
+///     "org.example.Node",
+///     "Changed",
+///     "org.example.BodySignature",
+/// )
+/// .unwrap();
+/// assert_eq!(value.as_deref(), Some("(sa{sv})"));
+/// ```
+pub fn get_signal_annotation(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+    annotation_name: &str,
+) -> Result<Option<String>> {
+    let signal = find_signal(xml, interface_name, member_name)?;
+
+    Ok(signal
+        .annotations()
+        .iter()
+        .find(|annotation| annotation.name() == annotation_name)
+        .map(|annotation| annotation.value().to_owned()))
+}
+
+/// Retrieve the signatures of a signal's arguments from XML, one per argument.
+///
+/// Unlike [`get_signal_body_type`], which concatenates the argument types into a single
+/// signature, this keeps the arguments apart. That tells a body of two flat arguments, `s u`,
+/// from a body of a single struct argument, `(su)`, which both yield `(su)` as body type.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::get_signal_arg_types;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <signal name="Flat">
+///     <arg name="name" type="s"/>
+///     <arg name="id" type="u"/>
+///   </signal>
+///   <signal name="Wrapped">
+///     <arg name="node" type="(su)"/>
+///   </signal>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let flat = get_signal_arg_types(xml.as_bytes(), "org.example.Node", "Flat").unwrap();
+/// assert_eq!(flat.len(), 2);
+///
+/// let wrapped = get_signal_arg_types(xml.as_bytes(), "org.example.Node", "Wrapped").unwrap();
+/// assert_eq!(wrapped.len(), 1);
+/// ```
+pub fn get_signal_arg_types(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<Vec<Signature>> {
+    let signal = find_signal(xml, interface_name, member_name)?;
+
+    signal
+        .args()
+        .iter()
+        .map(|arg| Ok(Signature::from_str(&arg.ty().to_string()).map_err(|_| "Invalid signature")?))
+        .collect()
+}
+
+/// Retrieve the signatures of a method's in-args from XML, one per argument.
+///
+/// This is the method counterpart of [`get_signal_arg_types`]: it tells several flat in-args
+/// from a single struct in-arg, which both yield the same signature from
+/// [`get_method_args_type`].
+///
+/// Arguments without a `direction` attribute are in-args, as the D-Bus specification defines.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::get_method_in_arg_types;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <method name="CreateNode">
+///     <arg name="name" type="s" direction="in"/>
+///     <arg name="id" type="u" direction="in"/>
+///     <arg name="created" type="b" direction="out"/>
+///   </method>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let in_args = get_method_in_arg_types(xml.as_bytes(), "org.example.Node", "CreateNode").unwrap();
+/// assert_eq!(in_args.len(), 2);
+/// ```
+pub fn get_method_in_arg_types(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<Vec<Signature>> {
+    let method = find_method(xml, interface_name, member_name)?;
+
+    method
+        .args()
+        .iter()
+        .filter(|arg| arg.direction() != Some(Out))
+        .map(|arg| Ok(Signature::from_str(&arg.ty().to_string()).map_err(|_| "Invalid signature")?))
+        .collect()
+}
+
+/// Retrieve the signatures of a method's out-args from XML, one per argument.
+///
+/// The out-arg counterpart of [`get_method_in_arg_types`].
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::get_method_out_arg_types;
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <method name="CreateNode">
+///     <arg name="name" type="s" direction="in"/>
+///     <arg name="created" type="b" direction="out"/>
+///     <arg name="path" type="o" direction="out"/>
+///   </method>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// let out_args = get_method_out_arg_types(xml.as_bytes(), "org.example.Node", "CreateNode").unwrap();
+/// assert_eq!(out_args.len(), 2);
+/// assert_eq!(out_args[1].to_string(), "o");
+/// ```
+pub fn get_method_out_arg_types(
+    xml: impl Read,
+    interface_name: &str,
+    member_name: &str,
+) -> Result<Vec<Signature>> {
+    let method = find_method(xml, interface_name, member_name)?;
+
+    method
+        .args()
+        .iter()
+        .filter(|arg| arg.direction() == Some(Out))
+        .map(|arg| Ok(Signature::from_str(&arg.ty().to_string()).map_err(|_| "Invalid signature")?))
+        .collect()
+}
+
+/// Compose a signal body signature, leaving out a trailing `a{sv}` "metadata" argument.
+///
+/// If `arg_types` holds exactly one argument more than `expected` has fields, and that last
+/// argument is `a{sv}`, the body is composed of the remaining arguments. Otherwise, the body is
+/// composed of all arguments, as [`get_signal_body_type`] does.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::strip_trailing_metadata;
+/// use zvariant::Signature;
+///
+/// let arg_types = [
+///     Signature::from_str("s").unwrap(),
+///     Signature::from_str("u").unwrap(),
+///     Signature::from_str("a{sv}").unwrap(),
+/// ];
+/// let expected = Signature::from_str("(su)").unwrap();
+///
+/// assert_eq!(strip_trailing_metadata(&arg_types, &expected), expected);
+/// ```
+pub fn strip_trailing_metadata(arg_types: &[Signature], expected: &Signature) -> Signature {
+    let expected_fields = match expected {
+        Signature::Unit => 0,
+        Signature::Structure(fields) => fields.iter().count(),
+        _ => 1,
+    };
+
+    let arg_types = match arg_types.split_last() {
+        Some((last, rest)) if rest.len() == expected_fields && last.to_string() == "a{sv}" => rest,
+        _ => arg_types,
+    };
+
+    let signature = arg_types
+        .iter()
+        .map(ToString::to_string)
+        .collect::<String>();
+    Signature::from_str(&signature)
+        .expect("concatenated argument types should be a valid signature")
+}
+
 /// Retrieve the signature of a property's type from XML.
 ///
 /// # Examples
@@ -163,7 +527,7 @@ pub fn get_property_type(
 ) -> Result<Signature> {
     let node = Node::from_reader(&mut xml)?;
 
-    let interfaces = node.interfaces();
+    let interfaces = all_interfaces(&node);
     let interface = interfaces
         .iter()
         .find(|iface| iface.name() == interface_name)
@@ -224,34 +588,20 @@ pub fn get_property_type(
 /// assert_eq!(signature, *Role::SIGNATURE);
 /// ```
 pub fn get_method_return_type(
-    mut xml: impl Read,
+    xml: impl Read,
     interface_name: &str,
     member_name: &str,
     arg_name: Option<&str>,
 ) -> Result<Signature> {
-    let node = Node::from_reader(&mut xml)?;
-
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_string()))?;
-
-    let methods = interface.methods();
-    let method = methods
-        .iter()
-        .find(|method| method.name() == member_name)
-        .ok_or(MemberNotFound(member_name.to_string()))?;
+    let method = find_method(xml, interface_name, member_name)?;
 
     let args = method.args();
 
     let signature = {
-        if arg_name.is_some() {
+        if let Some(arg_name) = arg_name {
             args.iter()
-                .find(|arg| arg.name() == arg_name)
-                .ok_or(ArgumentNotFound(
-                    arg_name.expect("arg_name guarded by 'is_some'").to_string(),
-                ))?
+                .find(|arg| arg.name() == Some(arg_name))
+                .ok_or(ArgumentNotFound(arg_name.to_string()))?
                 .ty()
                 .to_string()
         } else {
@@ -271,7 +621,7 @@ pub fn get_method_return_type(
 ///
 /// If you provide an argument name, then the signature of that argument is returned.
 /// If you do not provide an argument name, then the signature of all arguments to the call is
-/// returned.
+/// returned. Arguments without a `direction` attribute are in-args.
 ///
 /// # Examples
 ///
@@ -324,38 +674,24 @@ pub fn get_method_return_type(
 /// assert_eq!(&signature, Notification::SIGNATURE);
 /// ```
 pub fn get_method_args_type(
-    mut xml: impl Read,
+    xml: impl Read,
     interface_name: &str,
     member_name: &str,
     arg_name: Option<&str>,
 ) -> Result<Signature> {
-    let node = Node::from_reader(&mut xml)?;
-
-    let interfaces = node.interfaces();
-    let interface = interfaces
-        .iter()
-        .find(|iface| iface.name() == interface_name)
-        .ok_or(InterfaceNotFound(interface_name.to_owned()))?;
-
-    let methods = interface.methods();
-    let method = methods
-        .iter()
-        .find(|method| method.name() == member_name)
-        .ok_or(member_name.to_owned())?;
+    let method = find_method(xml, interface_name, member_name)?;
 
     let args = method.args();
 
-    let signature = if arg_name.is_some() {
+    let signature = if let Some(arg_name) = arg_name {
         args.iter()
-            .find(|arg| arg.name() == arg_name)
-            .ok_or(ArgumentNotFound(
-                arg_name.expect("arg_name guarded by is_some").to_string(),
-            ))?
+            .find(|arg| arg.name() == Some(arg_name))
+            .ok_or(ArgumentNotFound(arg_name.to_string()))?
             .ty()
             .to_string()
     } else {
         args.iter()
-            .filter(|arg| arg.direction() == Some(In))
+            .filter(|arg| arg.direction() != Some(Out))
             .map(|arg| arg.ty().to_string())
             .collect::<String>()
     };
@@ -363,14 +699,125 @@ pub fn get_method_args_type(
     Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
 }
 
+/// Strip whitespace from the `type` attributes in `DBus` XML.
+///
+/// Hand-edited XML may contain stray whitespace in `type` attributes, which fails to parse
+/// as a signature. This removes all whitespace from `type` attribute values, and leaves the
+/// rest of the document untouched.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::trim_type_attributes;
+///
+/// let xml = r#"<arg name="path" type=" (s o) "/>"#;
+/// assert_eq!(trim_type_attributes(xml), r#"<arg name="path" type="(so)"/>"#);
+/// ```
+pub fn trim_type_attributes(xml: &str) -> String {
+    let mut trimmed = String::with_capacity(xml.len());
+    let mut rest = xml;
+
+    while let Some(start) = find_type_attribute(rest) {
+        let (before, attribute) = rest.split_at(start);
+        trimmed.push_str(before);
+
+        // `attribute` starts with `type`, followed by optional whitespace, `=`, optional
+        // whitespace and the quoted value.
+        let quote_pos = attribute
+            .find(['"', '\''])
+            .expect("type attribute has a quoted value");
+        let quote = &attribute[quote_pos..=quote_pos];
+        trimmed.push_str(&attribute[..=quote_pos]);
+
+        let value = &attribute[quote_pos + 1..];
+        let end = value.find(quote).unwrap_or(value.len());
+        trimmed.extend(value[..end].chars().filter(|c| !c.is_whitespace()));
+
+        rest = &value[end..];
+    }
+
+    trimmed.push_str(rest);
+    trimmed
+}
+
+/// Find the start of the next ` type="` or ` type='` attribute.
+fn find_type_attribute(xml: &str) -> Option<usize> {
+    let mut offset = 0;
+
+    while let Some(pos) = xml[offset..].find("type") {
+        let start = offset + pos;
+        let preceded_by_whitespace = xml[..start]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_whitespace);
+        let followed_by_value = xml[start + "type".len()..]
+            .trim_start()
+            .strip_prefix('=')
+            .map(str::trim_start)
+            .is_some_and(|value| value.starts_with(['"', '\'']));
+
+        if preceded_by_whitespace && followed_by_value {
+            return Some(start);
+        }
+        offset = start + "type".len();
+    }
+
+    None
+}
+
+/// Render the `<arg>` elements a signal declaration needs to carry a type as its body.
+///
+/// Useful for scaffolding new `DBus` XML from existing types. Each top-level field of the
+/// type's signature becomes one argument. Rust types carry no field names at runtime, so these
+/// are passed in `field_names`, in declaration order. Arguments without a name in
+/// `field_names` are rendered without a `name` attribute.
+///
+/// # Examples
+///
+/// ```rust
+/// use zvariant::{OwnedObjectPath, Type};
+/// use zbus_lockstep::struct_to_xml_args;
+///
+/// #[derive(Type)]
+/// struct RemoveNode {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// let args = struct_to_xml_args::<RemoveNode>(&["name", "path"]);
+/// assert_eq!(args, "<arg type=\"s\" name=\"name\"/>\n<arg type=\"o\" name=\"path\"/>\n");
+/// ```
+pub fn struct_to_xml_args<T: Type>(field_names: &[&str]) -> String {
+    let fields: Vec<&Signature> = match T::SIGNATURE {
+        Signature::Unit => Vec::new(),
+        Signature::Structure(fields) => fields.iter().collect(),
+        signature => vec![signature],
+    };
+
+    fields
+        .into_iter()
+        .enumerate()
+        .map(|(i, signature)| match field_names.get(i) {
+            Some(name) => format!("<arg type=\"{signature}\" name=\"{name}\"/>\n"),
+            None => format!("<arg type=\"{signature}\"/>\n"),
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
-    use std::io::{Seek, SeekFrom, Write};
+    use std::{
+        io::{Seek, SeekFrom, Write},
+        str::FromStr,
+    };
 
     use tempfile::tempfile;
     use zvariant::{OwnedObjectPath, Type};
 
-    use crate::get_signal_body_type;
+    use crate::{
+        get_method_args_type, get_method_in_arg_types, get_method_return_type, get_property_type,
+        get_signal_annotation, get_signal_body_type, struct_to_xml_args,
+    };
 
     #[test]
     fn test_get_signature_of_cache_add_accessible() {
@@ -415,4 +862,99 @@ mod test {
         let signature = get_signal_body_type(xml_file, interface_name, member_name, None).unwrap();
         assert_eq!(signature, *CacheItem::SIGNATURE);
     }
+
+    #[test]
+    fn test_struct_to_xml_args_of_cache_item() {
+        #[derive(Debug, PartialEq, Type)]
+        struct Accessible {
+            name: String,
+            path: OwnedObjectPath,
+        }
+
+        #[derive(Debug, PartialEq, Type)]
+        struct CacheItem {
+            obj: Accessible,
+            index_in_parent: i32,
+            interfaces: Vec<String>,
+            state_set: Vec<u32>,
+        }
+
+        let fixture = r#"<arg type="(so)" name="obj"/>
+<arg type="i" name="index_in_parent"/>
+<arg type="as" name="interfaces"/>
+<arg type="au"/>
+"#;
+
+        let args = struct_to_xml_args::<CacheItem>(&["obj", "index_in_parent", "interfaces"]);
+        assert_eq!(args, fixture);
+    }
+
+    #[test]
+    fn test_get_signal_body_type_of_nested_node() {
+        let xml = std::fs::read_to_string("../xml/nested/org.example.Nested.xml").unwrap();
+
+        let signature =
+            get_signal_body_type(xml.as_bytes(), "org.example.Grandchild", "Deep", None).unwrap();
+        assert_eq!(signature, zvariant::Signature::from_str("us").unwrap());
+    }
+
+    #[test]
+    fn test_get_method_return_type_of_several_out_args() {
+        let xml = std::fs::read_to_string("../xml/method_replies/org.example.Window.xml").unwrap();
+
+        let signature =
+            get_method_return_type(xml.as_bytes(), "org.example.Window", "GetPosition", None)
+                .unwrap();
+        assert_eq!(&signature, <(i32, i32)>::SIGNATURE);
+    }
+
+    #[test]
+    fn test_get_method_return_type_of_single_out_arg() {
+        let xml = std::fs::read_to_string("../xml/method_replies/org.example.Window.xml").unwrap();
+
+        let signature =
+            get_method_return_type(xml.as_bytes(), "org.example.Window", "GetTitle", None).unwrap();
+        assert_eq!(signature, zvariant::Signature::Str);
+    }
+
+    #[test]
+    fn test_members_among_annotations_and_docs() {
+        let xml = std::fs::read_to_string("../xml/annotated/org.example.Annotated.xml").unwrap();
+        let interface = "org.example.Annotated";
+
+        let signature = get_signal_body_type(xml.as_bytes(), interface, "Renamed", None).unwrap();
+        assert_eq!(&signature, <(String, String, OwnedObjectPath)>::SIGNATURE);
+
+        // The annotations are there to be read, not only skipped.
+        let deprecated = get_signal_annotation(
+            xml.as_bytes(),
+            interface,
+            "Renamed",
+            "org.freedesktop.DBus.Deprecated",
+        )
+        .unwrap();
+        assert_eq!(deprecated.as_deref(), Some("true"));
+
+        let signature = get_method_args_type(xml.as_bytes(), interface, "Rename", None).unwrap();
+        assert_eq!(&signature, <(OwnedObjectPath, String)>::SIGNATURE);
+
+        let signature = get_property_type(xml.as_bytes(), interface, "Name").unwrap();
+        assert_eq!(signature, zvariant::Signature::Str);
+    }
+
+    #[test]
+    fn test_method_args_without_direction_are_in_args() {
+        let xml =
+            std::fs::read_to_string("../xml/default_direction/org.example.Settings.xml").unwrap();
+        let interface = "org.example.Settings";
+
+        let in_args = get_method_in_arg_types(xml.as_bytes(), interface, "Set").unwrap();
+        assert_eq!(
+            in_args,
+            [zvariant::Signature::Str, zvariant::Signature::U32]
+        );
+
+        let signature = get_method_args_type(xml.as_bytes(), interface, "Set", None).unwrap();
+        assert_eq!(&signature, <(String, u32)>::SIGNATURE);
+    }
 }
@@ -0,0 +1,143 @@
+use std::{io::ErrorKind, process::Command};
+
+use crate::{LockstepError::IntrospectionFailed, Result};
+
+/// A message bus to introspect services on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bus {
+    Session,
+    System,
+}
+
+/// Introspect the object at `path` of the running service `destination` on `bus`.
+///
+/// Returns the introspection XML as it is returned by the service's
+/// `org.freedesktop.DBus.Introspectable.Introspect` method.
+///
+/// The call is made with `dbus-send`, the command line client of the reference D-Bus
+/// implementation, which must be on `PATH`. The bus must be reachable, for the session bus
+/// through `DBUS_SESSION_BUS_ADDRESS`, and `destination` must be running or activatable.
+///
+/// # Errors
+///
+/// Fails with [`LockstepError::IntrospectionFailed`] if `dbus-send` cannot be run, if the bus
+/// or the service returns an error, whose D-Bus error name is kept, or if the reply is not
+/// introspection XML.
+///
+/// [`LockstepError::IntrospectionFailed`]: crate::LockstepError::IntrospectionFailed
+///
+/// # Examples
+///
+/// ```no_run
+/// use zbus_lockstep::{introspect, Bus};
+///
+/// let xml = introspect(Bus::Session, "org.freedesktop.DBus", "/org/freedesktop/DBus").unwrap();
+/// assert!(xml.contains("org.freedesktop.DBus.Introspectable"));
+/// ```
+pub fn introspect(bus: Bus, destination: &str, path: &str) -> Result<String> {
+    let failed = |error_name: Option<String>, message: String| IntrospectionFailed {
+        destination: destination.to_owned(),
+        path: path.to_owned(),
+        error_name,
+        message,
+    };
+
+    let bus = match bus {
+        Bus::Session => "--session",
+        Bus::System => "--system",
+    };
+
+    let output = Command::new("dbus-send")
+        .args([bus, "--print-reply=literal"])
+        .arg(format!("--dest={destination}"))
+        .arg(path)
+        .arg("org.freedesktop.DBus.Introspectable.Introspect")
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => failed(None, "`dbus-send` is not on `PATH`.".to_owned()),
+            _ => failed(None, format!("Failed to run `dbus-send`: {e}")),
+        })?;
+
+    if !output.status.success() {
+        let (error_name, message) = reply_error(&String::from_utf8_lossy(&output.stderr));
+        return Err(failed(error_name, message).into());
+    }
+
+    let reply = String::from_utf8(output.stdout)
+        .map_err(|_| failed(None, "The reply is not valid UTF-8.".to_owned()))?;
+    reply_xml(&reply).ok_or_else(|| {
+        failed(
+            None,
+            format!("The reply is not introspection XML: \"{}\"", reply.trim()),
+        )
+        .into()
+    })
+}
+
+// `dbus-send` indents the string it prints.
+fn reply_xml(reply: &str) -> Option<String> {
+    let xml = reply.trim();
+    xml.starts_with('<').then(|| xml.to_owned())
+}
+
+// `dbus-send` reports an error reply as `Error <name>: <message>`, and failures of its own, such
+// as an unreachable bus, as free text.
+fn reply_error(stderr: &str) -> (Option<String>, String) {
+    let stderr = stderr.trim();
+
+    match stderr
+        .strip_prefix("Error ")
+        .and_then(|error| error.split_once(": "))
+    {
+        Some((name, message)) if !name.contains(char::is_whitespace) => {
+            (Some(name.to_owned()), message.to_owned())
+        }
+        _ => (None, stderr.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{reply_error, reply_xml};
+
+    #[test]
+    fn test_reply_xml() {
+        let reply = "   <node>\n  <interface name=\"org.example.Node\"/>\n</node>\n";
+        assert_eq!(
+            reply_xml(reply).unwrap(),
+            "<node>\n  <interface name=\"org.example.Node\"/>\n</node>"
+        );
+    }
+
+    #[test]
+    fn test_reply_xml_rejects_other_output() {
+        assert_eq!(reply_xml("   string \"<node/>\"\n"), None);
+        assert_eq!(reply_xml(""), None);
+    }
+
+    #[test]
+    fn test_reply_error() {
+        let stderr = "Error org.freedesktop.DBus.Error.ServiceUnknown: The name org.example.Node \
+                      was not provided by any .service files\n";
+        assert_eq!(
+            reply_error(stderr),
+            (
+                Some("org.freedesktop.DBus.Error.ServiceUnknown".to_owned()),
+                "The name org.example.Node was not provided by any .service files".to_owned()
+            )
+        );
+    }
+
+    #[test]
+    fn test_reply_error_without_name() {
+        let stderr = "Failed to open connection to \"session\" message bus: Unable to autolaunch\n";
+        assert_eq!(
+            reply_error(stderr),
+            (
+                None,
+                "Failed to open connection to \"session\" message bus: Unable to autolaunch"
+                    .to_owned()
+            )
+        );
+    }
+}
@@ -2,19 +2,25 @@
 #![allow(dead_code)]
 #![allow(unused_imports)]
 
-use std::{fs, path::PathBuf, str::FromStr};
+use std::{
+    ffi::{OsStr, OsString},
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use crate::Result;
+use crate::{xml_dir::split_glob_pattern, Result};
 
 /// Resolve XML path from either:
 ///
 /// - provided argument,
 /// - default location (`xml/`, `XML/`, `../xml` or `../XML`) or
-/// - env_variable (`LOCKSTEP_XML_PATH`)
+/// - env_variable (`ZBUS_LOCKSTEP_XML_PATH`, or `LOCKSTEP_XML_PATH`)
 ///
 /// If no XML path is provided, it tries to find the default XML path.
 /// If the environment variable is set, it overrides the default, or
-/// argument path.
+/// argument path. `LOCKSTEP_XML_PATH`, its earlier name, is read if
+/// `ZBUS_LOCKSTEP_XML_PATH` is not set.
 ///
 /// # Example
 ///
@@ -23,33 +29,97 @@ use crate::Result;
 /// # use std::path::PathBuf;
 /// # fn main() {
 /// // path to XML files
-/// std::env::set_var("LOCKSTEP_XML_PATH", "../xml");
+/// std::env::set_var("ZBUS_LOCKSTEP_XML_PATH", "../xml");
 ///
 /// let xml_path = resolve_xml_path(None).unwrap();
 /// assert_eq!(xml_path, PathBuf::from("../xml").canonicalize().unwrap());
 /// # }
 /// ```
+/// The default locations are relative to the root of the crate being compiled, as set by
+/// Cargo in `CARGO_MANIFEST_DIR`. In a workspace, this is the member crate, not the workspace
+/// root that `cargo` is invoked from.
+///
+/// If the environment variable lists several paths, the first is returned, see
+/// [`resolve_xml_paths`].
+///
 /// # Panics
 ///
 /// Panics if no XML path is provided and the default XML path is not found.
 pub fn resolve_xml_path(xml: Option<&str>) -> Result<PathBuf> {
+    resolve_xml_path_in(&crate_dir(), xml)
+}
+
+/// Resolve XML paths like [`resolve_xml_path`], allowing several in `ZBUS_LOCKSTEP_XML_PATH`.
+///
+/// The environment variable is read as a list of paths, separated like `PATH`: by `:` on
+/// Unix and by `;` on Windows. A single path resolves as before. Every listed path must exist.
+///
+/// A glob pattern, as [`read_xml_files`](crate::read_xml_files) takes, resolves to the same
+/// pattern below each path its leading components without wildcards resolve to.
+///
+/// # Example
+///
+/// ```rust
+/// # use zbus_lockstep::resolve_xml_paths;
+/// # use std::path::PathBuf;
+/// # fn main() {
+/// let xml_paths = std::env::join_paths(["../xml", "../xml/methods"]).unwrap();
+/// std::env::set_var("ZBUS_LOCKSTEP_XML_PATH", xml_paths);
+///
+/// let xml_paths = resolve_xml_paths(None).unwrap();
+/// assert_eq!(xml_paths.len(), 2);
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// Panics if no XML path is provided and the default XML path is not found.
+pub fn resolve_xml_paths(xml: Option<&str>) -> Result<Vec<PathBuf>> {
+    resolve_xml_paths_in(&crate_dir(), xml)
+}
+
+/// The root of the crate being compiled, `CARGO_MANIFEST_DIR`.
+///
+/// Falls back to the current directory if Cargo did not set `CARGO_MANIFEST_DIR`.
+fn crate_dir() -> PathBuf {
+    std::env::var_os("CARGO_MANIFEST_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| {
+            std::env::current_dir().expect("the current directory should be accessible")
+        })
+}
+
+fn resolve_xml_path_in(crate_root: &Path, xml: Option<&str>) -> Result<PathBuf> {
+    Ok(resolve_xml_paths_in(crate_root, xml)?.swap_remove(0))
+}
+
+fn resolve_xml_paths_in(crate_root: &Path, xml: Option<&str>) -> Result<Vec<PathBuf>> {
+    // A glob pattern resolves to the pattern below each path its literal base resolves to.
+    if let Some((base, pattern)) = xml.and_then(split_glob_pattern) {
+        return Ok(resolve_xml_paths_in(crate_root, Some(&base))?
+            .into_iter()
+            .map(|base| base.join(&pattern))
+            .collect());
+    }
+
+    // Override the default, or argument path if the environment variable is set.
+    if let Some((var, env_xml_paths)) = env_xml_paths() {
+        return split_env_xml_paths(var, &env_xml_paths);
+    }
+
     let mut xml = xml;
-    let current_dir: PathBuf = PathBuf::from(
-        std::env::var("CARGO_MANIFEST_DIR")
-            .expect("the CARGO_MANIFEST_DIR environment variable should be set"),
-    );
 
     // We want to know the name of the crate we are expanded in.
     let crate_name = std::env::var("CARGO_PKG_NAME").unwrap_or_else(|_| String::from("unknown"));
 
-    let current_dir_lower_case = current_dir.join("xml");
-    let current_dir_upper_case = current_dir.join("XML");
+    let current_dir_lower_case = crate_root.join("xml");
+    let current_dir_upper_case = crate_root.join("XML");
 
-    let parent_dir_lower_case = current_dir.join("../xml");
-    let parent_dir_upper_case = current_dir.join("../XML");
+    let parent_dir_lower_case = crate_root.join("../xml");
+    let parent_dir_upper_case = crate_root.join("../XML");
 
-    let crate_dir_lower_case = current_dir.join(&crate_name).join("xml");
-    let crate_dir_upper_case = current_dir.join(&crate_name).join("XML");
+    let crate_dir_lower_case = crate_root.join(&crate_name).join("xml");
+    let crate_dir_upper_case = crate_root.join(&crate_name).join("XML");
 
     // If no XML path is provided, try to find the default XML path.
     if xml.is_none() {
@@ -102,23 +172,50 @@ pub fn resolve_xml_path(xml: Option<&str>) -> Result<PathBuf> {
         }
     }
 
-    let env_xml_path = std::env::var("LOCKSTEP_XML_PATH");
-    if env_xml_path.is_ok() {
-        // Override the default, or argument path if the environment variable is set.
-        xml = env_xml_path.as_ref().map(|s| s.as_str()).ok();
-    }
-
     // If no XML path is provided and the default XML path is not found, panic.
+    // The locations are named relative to the crate root, so the message is the same on any
+    // machine.
     if xml.is_none() {
         panic!(
-            "No XML path provided and default XML path not found. Current dir: \"{}\" ",
-            current_dir.to_str().expect("current_dir is valid UTF-8")
+            "No XML path provided and default XML path not found. Looked for `xml/` and `XML/` \
+             in the root of crate `{crate_name}` (`CARGO_MANIFEST_DIR`), in its parent and in \
+             `{crate_name}/`."
         );
     }
 
     // Convert, canonicalize and return the XML path.
     let xml = PathBuf::from_str(xml.unwrap())?;
-    Ok(xml.canonicalize()?)
+    Ok(vec![xml.canonicalize()?])
+}
+
+// The environment variables overriding the XML path, in order of precedence.
+// `LOCKSTEP_XML_PATH` is the name the variable had before, and is still read.
+const XML_PATH_VARS: [&str; 2] = ["ZBUS_LOCKSTEP_XML_PATH", "LOCKSTEP_XML_PATH"];
+
+/// The first of [`XML_PATH_VARS`] that is set, with its value.
+fn env_xml_paths() -> Option<(&'static str, OsString)> {
+    XML_PATH_VARS
+        .into_iter()
+        .find_map(|var| std::env::var_os(var).map(|paths| (var, paths)))
+}
+
+/// Split and canonicalize the paths listed in the environment variable `var`, naming any that
+/// is missing.
+fn split_env_xml_paths(var: &str, env_xml_paths: &OsStr) -> Result<Vec<PathBuf>> {
+    let xml_paths: Vec<PathBuf> = std::env::split_paths(env_xml_paths)
+        .filter(|path| !path.as_os_str().is_empty())
+        .collect();
+    if xml_paths.is_empty() {
+        return Err(format!("`{var}` is set, but lists no path.").into());
+    }
+
+    xml_paths
+        .into_iter()
+        .map(|path| {
+            path.canonicalize()
+                .map_err(|e| format!("`{var}` entry \"{}\" not found: {e}", path.display()).into())
+        })
+        .collect()
 }
 
 /// A generic helper to find the file path and interface name of a member.
@@ -151,7 +248,7 @@ macro_rules! find_definition_in_dbus_xml {
         let file = std::fs::File::open(entry.path()).expect("Failed to open file");
         let node = $crate::zbus_xml::Node::from_reader(file).expect("Failed to parse XML file");
 
-        for interface in node.interfaces() {
+        for interface in $crate::all_interfaces(&node) {
             // If called with an `iface` arg, skip he interfaces that do not match.
             if iface.is_some() && interface.name().as_str() != iface.clone().unwrap()  {
                 continue;
@@ -265,11 +362,8 @@ macro_rules! method_return_signature {
         let member = $member;
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -291,11 +385,8 @@ macro_rules! method_return_signature {
         let interface = Some($interface.to_string());
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -318,11 +409,8 @@ macro_rules! method_return_signature {
         let argument = Some($argument);
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -385,11 +473,8 @@ macro_rules! method_args_signature {
         let member = $member;
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -411,11 +496,8 @@ macro_rules! method_args_signature {
         let interface = Some($interface.to_string());
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -438,12 +520,8 @@ macro_rules! method_args_signature {
         let argument = Some($argument);
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
             $crate::find_definition_in_dbus_xml!(xml_path, member, interface, MsgType::Method);
@@ -502,11 +580,8 @@ macro_rules! signal_body_type_signature {
         let member = $member;
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -528,11 +603,8 @@ macro_rules! signal_body_type_signature {
         let interface = Some($interface.to_string());
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -555,12 +627,8 @@ macro_rules! signal_body_type_signature {
         let argument = Some($argument);
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -615,11 +683,8 @@ macro_rules! property_type_signature {
         let member = $member;
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -641,11 +706,8 @@ macro_rules! property_type_signature {
         let interface = Some($interface.to_string());
 
         // Looking for default path or path specified by environment variable.
-        let current_dir: std::path::PathBuf = std::env::current_dir().unwrap();
-        let xml_path = $crate::resolve_xml_path(None).expect(&format!(
-            "Failed to resolve XML path, current dir: {}",
-            current_dir.to_str().unwrap()
-        ));
+        let xml_path = $crate::resolve_xml_path(None)
+            .unwrap_or_else(|e| panic!("Failed to resolve XML path: {e}"));
 
         // Find the definition of the method in the XML specification.
         let (file_path, interface_name) =
@@ -661,14 +723,353 @@ macro_rules! property_type_signature {
     };
 }
 
+/// Assert that several signals of an interface have identical body signatures.
+///
+/// Some interfaces deliberately declare signals sharing a body, e.g. `Added` and `Removed`.
+/// This asserts that invariant of the XML itself, no Rust types are involved. `xml` is the path
+/// to the XML file that declares the interface. Panics naming the first signal whose body
+/// differs from that of the first signal listed.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::assert_signals_same_body;
+///
+/// assert_signals_same_body!(
+///     "../xml/test_definition_file.xml",
+///     "org.example.Node",
+///     ["AddNode", "RemoveNode"]
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_signals_same_body {
+    ($xml:expr, $interface:expr, [$first:expr $(, $signal:expr)* $(,)?] $(,)?) => {{
+        let xml_path = $xml;
+        let interface_name: &str = $interface;
+        let xml = $crate::read_xml_file(&xml_path).unwrap_or_else(|e| panic!("{e}"));
+
+        let body = |signal_name: &str| {
+            $crate::get_signal_body_type(xml.as_bytes(), interface_name, signal_name, None)
+                .unwrap_or_else(|e| {
+                    panic!("Failed to get body type of signal \"{signal_name}\": {e}")
+                })
+        };
+
+        let first_name: &str = $first;
+        let first_body = body(first_name);
+
+        $(
+            let signal_name: &str = $signal;
+            let signal_body = body(signal_name);
+            assert!(
+                signal_body == first_body,
+                "Signal \"{}\" has body \"{}\", but \"{}\" has body \"{}\"",
+                signal_name,
+                signal_body,
+                first_name,
+                first_body
+            );
+        )*
+    }};
+}
+
+/// Assert that two types have the same signature.
+///
+/// Useful when splitting or merging types, to guard against accidental changes to what is
+/// sent over the bus. This compares `<A as Type>::SIGNATURE` to `<B as Type>::SIGNATURE`,
+/// no XML is involved.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::assert_same_signature;
+/// use zvariant::{OwnedObjectPath, Type};
+///
+/// #[derive(Type)]
+/// struct AddNode {
+///     name: String,
+///     path: OwnedObjectPath,
+/// }
+///
+/// #[derive(Type)]
+/// struct RemoveNode {
+///     name: String,
+///     path: OwnedObjectPath,
+/// }
+///
+/// assert_same_signature!(AddNode, RemoveNode);
+/// ```
+#[macro_export]
+macro_rules! assert_same_signature {
+    ($a:ty, $b:ty $(,)?) => {{
+        let a = <$a as $crate::zvariant::Type>::SIGNATURE;
+        let b = <$b as $crate::zvariant::Type>::SIGNATURE;
+
+        if let Err(mismatch) = $crate::compare_signatures(a, b) {
+            panic!(
+                "Signatures of `{}` and `{}` differ:\n{}",
+                stringify!($a),
+                stringify!($b),
+                mismatch.caret_diff(),
+            );
+        }
+    }};
+}
+
+/// Assert that two signatures are equal, showing where they diverge if they are not.
+///
+/// Accepts anything that displays as a signature, such as a [`Signature`] or a `&str`. The
+/// signatures are compared with [`compare_signatures`], with `left` as the expected one, or
+/// with [`compare_signatures_with`] if [`CompareOptions`] are given as a third argument. On
+/// failure, the [`SignatureMismatch`] is printed, followed by both signatures on aligned
+/// lines, with a caret under the first character at which they differ:
+///
+/// ```text
+/// Signatures differ: expected signature "(sa{sv}as)", found "(sa{sv}ao)", first differing at field 2.
+/// left:  (sa{sv}as)
+/// right: (sa{sv}ao)
+///                ^
+/// ```
+///
+/// [`Signature`]: crate::zvariant::Signature
+/// [`compare_signatures`]: crate::compare_signatures
+/// [`compare_signatures_with`]: crate::compare_signatures_with
+/// [`CompareOptions`]: crate::CompareOptions
+/// [`SignatureMismatch`]: crate::SignatureMismatch
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{assert_eq_signatures, CompareOptions};
+/// use zvariant::{OwnedObjectPath, OwnedValue, Type};
+///
+/// assert_eq_signatures!(<(String, OwnedObjectPath)>::SIGNATURE, "(so)");
+///
+/// let options = CompareOptions {
+///     allow_value_fields: true,
+///     ..Default::default()
+/// };
+/// assert_eq_signatures!("(so)", <(String, OwnedValue)>::SIGNATURE, options);
+/// ```
+#[macro_export]
+macro_rules! assert_eq_signatures {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::assert_eq_signatures!($left, $right, $crate::CompareOptions::default())
+    };
+    ($left:expr, $right:expr, $options:expr $(,)?) => {{
+        let left: $crate::zvariant::Signature = ::std::string::ToString::to_string(&$left)
+            .parse()
+            .expect("`left` should be a valid signature");
+        let right: $crate::zvariant::Signature = ::std::string::ToString::to_string(&$right)
+            .parse()
+            .expect("`right` should be a valid signature");
+
+        if let Err(mismatch) = $crate::compare_signatures_with(&left, &right, $options) {
+            panic!("Signatures differ: {mismatch}\n{}", mismatch.caret_diff());
+        }
+    }};
+}
+
+/// Assert that two types have different signatures.
+///
+/// The negative counterpart of [`assert_same_signature!`].
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::assert_different_signature;
+/// use zvariant::{OwnedObjectPath, Type};
+///
+/// #[derive(Type)]
+/// struct AddNode {
+///     name: String,
+///     path: OwnedObjectPath,
+/// }
+///
+/// #[derive(Type)]
+/// struct Alert {
+///     urgent: bool,
+/// }
+///
+/// assert_different_signature!(AddNode, Alert);
+/// ```
+#[macro_export]
+macro_rules! assert_different_signature {
+    ($a:ty, $b:ty $(,)?) => {{
+        let a = <$a as $crate::zvariant::Type>::SIGNATURE;
+        let b = <$b as $crate::zvariant::Type>::SIGNATURE;
+
+        assert!(
+            a != b,
+            "Signatures of `{}` and `{}` are both \"{}\"",
+            stringify!($a),
+            stringify!($b),
+            a
+        );
+    }};
+}
+
+/// A validation, such as a function generated by `#[validate(mode: "fn")]`, returning the
+/// message of a failing check.
+pub type Validation = fn() -> std::result::Result<(), String>;
+
+/// Run `validations` and panic listing every one that fails, rather than stopping at the first.
+///
+/// Each validation is named, for the report. See [`aggregate_validations!`] for a test
+/// running these functions.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::assert_validations;
+///
+/// fn validate_add_node() -> Result<(), String> {
+///     Ok(())
+/// }
+///
+/// assert_validations(&[("AddNode", validate_add_node)]);
+/// ```
+pub fn assert_validations(validations: &[(&str, Validation)]) {
+    let failures: Vec<String> = validations
+        .iter()
+        .filter_map(|(name, validation)| validation().err().map(|e| format!("`{name}`: {e}")))
+        .collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} validations failed:\n{}",
+        failures.len(),
+        validations.len(),
+        failures.join("\n")
+    );
+}
+
+/// Generate a single test, `name`, running the listed validation functions.
+///
+/// By default, `#[validate]` generates one test per type. With many types, the many small tests
+/// add to the size of the test binary and to the time `cargo test` takes to start. Instead,
+/// types can be validated with `#[validate(mode: "fn")]` and their functions listed here, to run
+/// as one test that reports all failures together, see [`assert_validations`].
+///
+/// The functions are registered by listing them: collecting every `#[validate]` in a crate
+/// automatically would take link-time registration, which this crate does not depend on. Their
+/// names are stable, `__validate_` followed by the type's name, or as set by `test_name`. With
+/// `validate_from_manifest!` and `aggregate = true`, the manifest's `validations` lists them
+/// instead.
+/// The tradeoff is granularity: a single type can no longer be run or filtered on its own, and
+/// a failure is reported for the test as a whole. Attributes before `name`, such as
+/// `#[ignore]`, are applied to the test.
+///
+/// # Examples
+///
+/// ```ignore
+/// use zbus_lockstep::aggregate_validations;
+/// use zbus_lockstep_macros::validate;
+///
+/// #[validate(mode: "fn")]
+/// #[derive(Type)]
+/// struct AddNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// #[validate(mode: "fn")]
+/// #[derive(Type)]
+/// struct RemoveNodeSignal {
+///    name: String,
+///    path: OwnedObjectPath,
+/// }
+///
+/// aggregate_validations!(lockstep_signatures, [__validate_AddNodeSignal, __validate_RemoveNodeSignal]);
+/// ```
+#[macro_export]
+macro_rules! aggregate_validations {
+    ($(#[$attr:meta])* $name:ident, [$($validation:path),* $(,)?] $(,)?) => {
+        #[cfg(test)]
+        #[test]
+        $(#[$attr])*
+        fn $name() {
+            $crate::assert_validations(&[
+                $((stringify!($validation), $validation as $crate::Validation)),*
+            ]);
+        }
+    };
+}
+
 #[cfg(test)]
 mod test {
-    use std::str::FromStr;
+    use std::{path::Path, str::FromStr};
 
     use zvariant::Signature;
 
+    use super::{resolve_xml_path_in, resolve_xml_paths_in, split_env_xml_paths};
     use crate::signal_body_type_signature;
 
+    #[test]
+    fn test_resolve_xml_path_in_workspace_member() {
+        // A workspace whose member crate keeps its `xml/` next to its own `Cargo.toml`.
+        let workspace = tempfile::tempdir().unwrap();
+        let member = workspace.path().join("member");
+        std::fs::create_dir_all(member.join("xml")).unwrap();
+        std::fs::write(workspace.path().join("Cargo.toml"), "[workspace]\n").unwrap();
+        std::fs::write(member.join("Cargo.toml"), "[package]\n").unwrap();
+
+        let xml = resolve_xml_path_in(&member, None).unwrap();
+        assert_eq!(xml, member.join("xml").canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_resolve_xml_path_in_reports_relative_locations() {
+        let crate_root = tempfile::tempdir().unwrap();
+
+        let panic = std::panic::catch_unwind(|| resolve_xml_path_in(crate_root.path(), None))
+            .expect_err("resolving without any XML directory should panic");
+        let msg = panic
+            .downcast_ref::<String>()
+            .expect("panic message should be a String");
+
+        assert!(msg.starts_with("No XML path provided and default XML path not found."));
+        assert!(msg.contains("`xml/` and `XML/`"));
+        assert!(!msg.contains(&*crate_root.path().to_string_lossy()));
+    }
+
+    #[test]
+    fn test_resolve_xml_paths_in_resolves_glob_base() {
+        let paths = resolve_xml_paths_in(Path::new("."), Some("../xml/glob/**/*.xml")).unwrap();
+        assert_eq!(
+            paths,
+            vec![Path::new("../xml/glob")
+                .canonicalize()
+                .unwrap()
+                .join("**/*.xml")]
+        );
+    }
+
+    #[test]
+    fn test_split_env_xml_paths() {
+        let single = split_env_xml_paths("ZBUS_LOCKSTEP_XML_PATH", "../xml".as_ref()).unwrap();
+        assert_eq!(single, vec![Path::new("../xml").canonicalize().unwrap()]);
+
+        let list = std::env::join_paths(["../xml", "../xml/methods"]).unwrap();
+        let paths = split_env_xml_paths("ZBUS_LOCKSTEP_XML_PATH", &list).unwrap();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new("../xml").canonicalize().unwrap(),
+                Path::new("../xml/methods").canonicalize().unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_env_xml_paths_names_missing_entry() {
+        let list = std::env::join_paths(["../xml", "../xml/does_not_exist"]).unwrap();
+        let err = split_env_xml_paths("LOCKSTEP_XML_PATH", &list).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("`LOCKSTEP_XML_PATH` entry \"../xml/does_not_exist\" not found"));
+    }
+
     #[test]
     fn test_signal_body_signature_macro() {
         // path to XML files can be set by environment variable
@@ -0,0 +1,169 @@
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{LazyLock, RwLock},
+};
+
+use zvariant::Signature;
+
+use crate::LockstepError::TypeNotRegistered;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+static TYPE_SIGNATURES: LazyLock<RwLock<HashMap<String, Signature>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Signatures of the types that are known without registration.
+const BUILTIN_TYPE_SIGNATURES: &[(&str, &str)] = &[
+    ("bool", "b"),
+    ("u8", "y"),
+    ("i16", "n"),
+    ("u16", "q"),
+    ("i32", "i"),
+    ("u32", "u"),
+    ("i64", "x"),
+    ("u64", "t"),
+    ("f64", "d"),
+    ("String", "s"),
+    ("&str", "s"),
+    ("ObjectPath", "o"),
+    ("OwnedObjectPath", "o"),
+    ("Signature", "g"),
+    ("OwnedSignature", "g"),
+    ("Value", "v"),
+    ("OwnedValue", "v"),
+    ("Fd", "h"),
+    ("OwnedFd", "h"),
+];
+
+/// Register the signature of a type by name.
+///
+/// This lets types that do not implement [`zvariant::Type`], such as domain-specific wrappers,
+/// take part in validation from type names, see [`signature_from_type_names`] and
+/// [`validate_signal_type_names`], which validates them against XML. Registering a name again
+/// replaces its signature.
+///
+/// [`validate_signal_type_names`]: crate::validate_signal_type_names
+///
+/// The `#[validate]` attribute macro does not consult the registry, the types it validates
+/// still need to implement [`zvariant::Type`].
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{register_type_signature, registered_type_signature};
+///
+/// register_type_signature("mycrate::Handle", "u").unwrap();
+///
+/// let signature = registered_type_signature("mycrate::Handle").unwrap();
+/// assert_eq!(signature.to_string(), "u");
+/// ```
+pub fn register_type_signature(type_name: &str, signature: &str) -> Result<()> {
+    let signature = Signature::from_str(signature).map_err(|_| "Invalid signature")?;
+
+    TYPE_SIGNATURES
+        .write()
+        .expect("type signature registry should not be poisoned")
+        .insert(type_name.to_owned(), signature);
+
+    Ok(())
+}
+
+/// Retrieve the signature registered for a type name, if any.
+pub fn registered_type_signature(type_name: &str) -> Option<Signature> {
+    TYPE_SIGNATURES
+        .read()
+        .expect("type signature registry should not be poisoned")
+        .get(type_name)
+        .cloned()
+}
+
+/// Compose a body signature from the names of the field types.
+///
+/// Each name is looked up in the registry first, see [`register_type_signature`], then among
+/// the basic types, e.g. `u32`, `String` or `OwnedObjectPath`. The signatures of the fields
+/// are concatenated, as [`get_signal_body_type`](crate::get_signal_body_type) does with the
+/// arguments of a signal, so the two can be compared.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{get_signal_body_type, register_type_signature, signature_from_type_names};
+///
+/// let xml = r#"
+/// <node>
+/// <interface name="org.example.Node">
+///   <signal name="Opened">
+///     <arg name="handle" type="u"/>
+///     <arg name="path" type="o"/>
+///   </signal>
+/// </interface>
+/// </node>
+/// "#;
+///
+/// register_type_signature("mycrate::Handle", "u").unwrap();
+///
+/// let from_names = signature_from_type_names(&["mycrate::Handle", "OwnedObjectPath"]).unwrap();
+/// let from_xml = get_signal_body_type(xml.as_bytes(), "org.example.Node", "Opened", None).unwrap();
+/// assert_eq!(from_names, from_xml);
+/// ```
+pub fn signature_from_type_names(type_names: &[&str]) -> Result<Signature> {
+    let mut signature = String::new();
+
+    for type_name in type_names {
+        match registered_type_signature(type_name) {
+            Some(registered) => signature.push_str(&registered.to_string()),
+            None => {
+                let (_, builtin) = BUILTIN_TYPE_SIGNATURES
+                    .iter()
+                    .find(|(name, _)| name == type_name)
+                    .ok_or(TypeNotRegistered((*type_name).to_owned()))?;
+                signature.push_str(builtin);
+            }
+        }
+    }
+
+    Ok(Signature::from_str(&signature).map_err(|_| "Invalid signature")?)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{register_type_signature, registered_type_signature, signature_from_type_names};
+
+    #[test]
+    fn test_register_type_signature_replaces_previous() {
+        register_type_signature("test::Replaced", "u").unwrap();
+        register_type_signature("test::Replaced", "s").unwrap();
+
+        let signature = registered_type_signature("test::Replaced").unwrap();
+        assert_eq!(signature.to_string(), "s");
+    }
+
+    #[test]
+    fn test_register_type_signature_rejects_invalid_signature() {
+        assert!(register_type_signature("test::Invalid", "(u").is_err());
+        assert!(registered_type_signature("test::Invalid").is_none());
+    }
+
+    #[test]
+    fn test_registry_takes_precedence_over_builtins() {
+        assert_eq!(
+            signature_from_type_names(&["String", "u32"])
+                .unwrap()
+                .to_string(),
+            "(su)"
+        );
+
+        // This overrides `u32` for every test in this binary, no other test composes it.
+        register_type_signature("u32", "t").unwrap();
+
+        let signature = signature_from_type_names(&["String", "u32"]).unwrap();
+        assert_eq!(signature.to_string(), "(st)");
+    }
+
+    #[test]
+    fn test_unknown_type_name_is_an_error() {
+        let err = signature_from_type_names(&["test::Unknown"]).unwrap_err();
+        assert_eq!(err.to_string(), "Type \"test::Unknown\" not registered.");
+    }
+}
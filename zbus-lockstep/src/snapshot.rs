@@ -0,0 +1,92 @@
+use std::{path::Path, str::FromStr};
+
+use zvariant::Signature;
+
+use crate::{LockstepError::SnapshotMismatch, Result};
+
+/// Check a signature against the snapshot stored at `snapshot`.
+///
+/// A snapshot is a text file holding a single signature. If the file does not exist yet, it is
+/// created with `signature`, so the first run records the snapshot and later runs compare
+/// against it. To accept a deliberate change, delete the snapshot file.
+///
+/// This catches drift of the XML itself, as opposed to drift of a type from the XML.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::check_signature_snapshot;
+/// use zvariant::Signature;
+///
+/// let dir = tempfile::tempdir().unwrap();
+/// let snapshot = dir.path().join("org.example.Node.RemoveNode.signature");
+///
+/// let signature = Signature::from_str("(so)").unwrap();
+/// check_signature_snapshot(&snapshot, &signature).unwrap();
+///
+/// let drifted = Signature::from_str("(su)").unwrap();
+/// assert!(check_signature_snapshot(&snapshot, &drifted).is_err());
+/// ```
+pub fn check_signature_snapshot(snapshot: impl AsRef<Path>, signature: &Signature) -> Result<()> {
+    let snapshot = snapshot.as_ref();
+
+    let contents = match std::fs::read_to_string(snapshot) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            if let Some(dir) = snapshot.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(snapshot, format!("{signature}\n"))?;
+            return Ok(());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let expected = Signature::from_str(contents.trim()).map_err(|_| "Invalid signature")?;
+    if &expected != signature {
+        return Err(SnapshotMismatch {
+            snapshot: snapshot.display().to_string(),
+            expected: expected.to_string(),
+            found: signature.to_string(),
+        }
+        .into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use zvariant::Signature;
+
+    use crate::check_signature_snapshot;
+
+    #[test]
+    fn test_snapshot_is_recorded_on_first_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = dir.path().join("nested").join("Ping.signature");
+
+        check_signature_snapshot(&snapshot, &Signature::from_str("u").unwrap()).unwrap();
+        assert_eq!(std::fs::read_to_string(&snapshot).unwrap(), "u\n");
+    }
+
+    #[test]
+    fn test_snapshot_mismatch() {
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot = dir.path().join("Ping.signature");
+        std::fs::write(&snapshot, "u\n").unwrap();
+
+        let err =
+            check_signature_snapshot(&snapshot, &Signature::from_str("s").unwrap()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Snapshot \"{}\" holds signature \"u\", but found \"s\".",
+                snapshot.display()
+            )
+        );
+    }
+}
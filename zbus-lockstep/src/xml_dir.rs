@@ -0,0 +1,741 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use zbus_xml::{Interface, Node, Signal};
+use zvariant::{Signature, Type};
+
+use crate::{compare_signatures, signature_from_type_names, LockstepError, Result};
+
+/// An interface declared in `DBus` XML, qualified by where it is declared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceEntry {
+    /// The XML file declaring the interface.
+    pub file: PathBuf,
+
+    /// The object path of the node declaring the interface.
+    ///
+    /// Nodes without a name are at `/`, child nodes with a relative name are below their parent.
+    pub path: String,
+
+    /// The interface itself.
+    pub interface: Interface<'static>,
+}
+
+/// Load the interfaces of all XML files in a directory.
+///
+/// Child nodes are walked recursively, so interfaces at any nesting depth are included, each
+/// with the object path of the node that declares it. The files are those
+/// [`read_xml_files`] selects without a filter, so subdirectories and files without an `.xml`
+/// extension, in any case, are skipped, and `dir` may also be a single file or a glob pattern.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{find_interface, load_xml_dir};
+///
+/// let interfaces = load_xml_dir("../xml").unwrap();
+///
+/// let node = find_interface(&interfaces, "/", "org.example.Node").unwrap();
+/// assert!(node.interface.signals().iter().any(|signal| signal.name() == "AddNode"));
+/// ```
+pub fn load_xml_dir(dir: impl AsRef<Path>) -> Result<Vec<InterfaceEntry>> {
+    load_xml_files(dir, None)
+}
+
+/// Load the interfaces of the XML files [`read_xml_files`] selects.
+fn load_xml_files(xml: impl AsRef<Path>, file_filter: Option<&str>) -> Result<Vec<InterfaceEntry>> {
+    let mut entries = Vec::new();
+
+    for (path, xml) in read_xml_files(xml, file_filter)? {
+        let node: Node<'static> = Node::from_reader(xml.as_bytes())?;
+        collect_interfaces(&node, &path, None, &mut entries);
+    }
+
+    Ok(entries)
+}
+
+/// Read the XML files selected by `xml`, with their file paths, sorted by path.
+///
+/// This is the walk `#[validate]` does, so both find the same files. `xml` is either
+///
+/// - an XML file, which is read as is,
+/// - a directory, of which the files with an `.xml` or `.xml.gz` extension, in any case, are
+///   read, skipping subdirectories, or
+/// - a glob pattern, where `*` and `?` match within a path component and `**` matches any number
+///   of directories. The leading components without wildcards form the directory the walk
+///   starts from.
+///
+/// If `file_filter` is provided, only files whose name matches it, with the same wildcards, are
+/// read. Each file is read with [`read_xml_file`], so compressed files are decompressed.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::read_xml_files;
+///
+/// let xml_files = read_xml_files("../xml/glob/**/org.freedesktop.*.xml", None).unwrap();
+///
+/// let names: Vec<_> = xml_files
+///     .iter()
+///     .map(|(path, _)| path.file_name().unwrap())
+///     .collect();
+/// assert_eq!(names, ["org.freedesktop.Beta.xml", "org.freedesktop.Alpha.xml"]);
+/// ```
+pub fn read_xml_files(
+    xml: impl AsRef<Path>,
+    file_filter: Option<&str>,
+) -> Result<Vec<(PathBuf, String)>> {
+    find_xml_files(xml, file_filter)?
+        .into_iter()
+        .map(|path| read_xml_file(&path).map(|xml| (path, xml)))
+        .collect()
+}
+
+/// The paths of the XML files [`read_xml_files`] reads for `xml` and `file_filter`, sorted by
+/// path, without reading them.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::Path;
+///
+/// use zbus_lockstep::find_xml_files;
+///
+/// let paths = find_xml_files("../xml/extensionless", None).unwrap();
+/// assert_eq!(paths, [Path::new("../xml/extensionless/org.example.Plain.xml")]);
+/// ```
+pub fn find_xml_files(xml: impl AsRef<Path>, file_filter: Option<&str>) -> Result<Vec<PathBuf>> {
+    let xml = xml.as_ref();
+
+    let mut paths = Vec::new();
+    match xml.to_str().and_then(split_glob_pattern) {
+        Some((base, pattern)) => {
+            let components: Vec<&str> = pattern.split('/').collect();
+            walk_glob(Path::new(&base), &components, &mut paths)?;
+        }
+        None if xml.is_file() => paths.push(xml.to_path_buf()),
+        None => paths = xml_dir_files(xml)?,
+    }
+
+    // Skip files that do not match the filter.
+    paths.retain(|path| {
+        file_filter.is_none_or(|filter| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            wildcard_match(filter, &name)
+        })
+    });
+
+    // Sort for a deterministic order of the files.
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Read the XML file at `path`.
+///
+/// A file whose name ends in `.xml.gz`, in any case, is gzip-compressed and decompressed
+/// first, which needs the `gzip` feature. Without it, reading such a file fails with an error
+/// saying so.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::read_xml_file;
+///
+/// let xml = read_xml_file("../xml/sole_signal/org.example.Notifier.xml").unwrap();
+/// assert!(xml.contains(r#"<signal name="Notified">"#));
+/// ```
+pub fn read_xml_file(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    let error = |e: &dyn std::fmt::Display| -> Box<dyn std::error::Error> {
+        format!("Failed to read XML file: \"{}\" Err: {e}", path.display()).into()
+    };
+
+    if !is_compressed(path) {
+        return std::fs::read_to_string(path).map_err(|e| error(&e));
+    }
+
+    #[cfg(feature = "gzip")]
+    {
+        let data = std::fs::read(path).map_err(|e| error(&e))?;
+        let xml = crate::gzip::decompress(&data).map_err(|e| error(&e))?;
+        String::from_utf8(xml).map_err(|e| error(&e))
+    }
+    #[cfg(not(feature = "gzip"))]
+    Err(error(
+        &"compressed XML is not supported, enable the `gzip` feature of `zbus-lockstep`, or of \
+          `zbus-lockstep-macros` for `#[validate]`",
+    ))
+}
+
+/// Whether the file name of `path` ends in `.xml.gz`, in any case.
+fn is_compressed(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.to_ascii_lowercase().ends_with(".xml.gz"))
+}
+
+/// Whether `xml` is a glob pattern rather than a path.
+fn is_glob_pattern(xml: &str) -> bool {
+    xml.contains(['*', '?'])
+}
+
+/// Split the glob pattern `xml` into the directory the walk starts from, its leading components
+/// without wildcards, and the pattern below it. `None` if `xml` is not a glob pattern.
+pub(crate) fn split_glob_pattern(xml: &str) -> Option<(String, String)> {
+    if !is_glob_pattern(xml) {
+        return None;
+    }
+
+    let components: Vec<&str> = xml.split('/').filter(|c| !c.is_empty()).collect();
+    let literal = components
+        .iter()
+        .take_while(|component| !is_glob_pattern(component))
+        .count();
+
+    let mut base = components[..literal].join("/");
+    if xml.starts_with('/') {
+        base.insert(0, '/');
+    }
+    if base.is_empty() {
+        base.push('.');
+    }
+
+    Some((base, components[literal..].join("/")))
+}
+
+/// Collect the XML files below `dir` that match the remaining pattern `components`.
+///
+/// `**` matches any number of directories, including none.
+fn walk_glob(dir: &Path, components: &[&str], paths: &mut Vec<PathBuf>) -> Result<()> {
+    match components {
+        [] => {}
+        ["**", rest @ ..] => {
+            // `**` at the end matches all files below `dir`.
+            walk_glob(dir, if rest.is_empty() { &["*"] } else { rest }, paths)?;
+            for subdir in subdirectories(dir)? {
+                walk_glob(&subdir, components, paths)?;
+            }
+        }
+        [file_pattern] => {
+            paths.extend(xml_dir_files(dir)?.into_iter().filter(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                wildcard_match(file_pattern, &name)
+            }));
+        }
+        [dir_pattern, rest @ ..] => {
+            for subdir in subdirectories(dir)? {
+                let name = subdir.file_name().unwrap_or_default().to_string_lossy();
+                if wildcard_match(dir_pattern, &name) {
+                    walk_glob(&subdir, rest, paths)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The paths in `dir` for which `keep` holds.
+fn dir_entries(dir: &Path, keep: impl Fn(&Path) -> bool) -> Result<Vec<PathBuf>> {
+    let read_dir_error =
+        |e: std::io::Error| format!("Failed to read XML directory \"{}\": {e}", dir.display());
+
+    // If the path does not exist, the process lacks permissions to read the path,
+    // or the path is not a directory, return an error.
+    let read_dir =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read XML directory: {e}"))?;
+
+    let mut paths = Vec::new();
+    for entry in read_dir {
+        let path = entry.map_err(read_dir_error)?.path();
+        if keep(&path) {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// The directories directly in `dir`.
+fn subdirectories(dir: &Path) -> Result<Vec<PathBuf>> {
+    dir_entries(dir, Path::is_dir)
+}
+
+/// The files directly in `dir` with an `.xml` or `.xml.gz` extension, in any case, such that a
+/// stray `README` is skipped.
+fn xml_dir_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    dir_entries(dir, |path| {
+        !path.is_dir()
+            && (is_compressed(path)
+                || path
+                    .extension()
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case("xml")))
+    })
+}
+
+/// Match `name` against `pattern`, where `*` matches any sequence and `?` any single character.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Positions to resume from when a mismatch follows the most recent `*`.
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    backtrack = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Find the interface named `interface_name` on the node at object path `path`.
+pub fn find_interface<'a>(
+    entries: &'a [InterfaceEntry],
+    path: &str,
+    interface_name: &str,
+) -> Option<&'a InterfaceEntry> {
+    entries
+        .iter()
+        .find(|entry| entry.path == path && entry.interface.name() == interface_name)
+}
+
+/// Find every signal in a directory of XML files whose body signature equals that of `T`.
+///
+/// This helps discover which signals a type could represent. Returns `(interface, signal)`
+/// pairs, sorted and without duplicates. Like [`load_xml_dir`], this includes the interfaces
+/// of nested nodes and skips subdirectories.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::find_matching_signals;
+/// use zvariant::OwnedObjectPath;
+///
+/// let matches = find_matching_signals::<(String, OwnedObjectPath)>("../xml").unwrap();
+/// assert!(matches.contains(&("org.example.Node".to_string(), "AddNode".to_string())));
+/// ```
+pub fn find_matching_signals<T: Type>(dir: impl AsRef<Path>) -> Result<Vec<(String, String)>> {
+    let mut matches = Vec::new();
+
+    for entry in load_xml_dir(dir)? {
+        for signal in entry.interface.signals() {
+            if &body_signature(signal)? == T::SIGNATURE {
+                matches.push((
+                    entry.interface.name().to_string(),
+                    signal.name().to_string(),
+                ));
+            }
+        }
+    }
+
+    matches.sort();
+    matches.dedup();
+
+    Ok(matches)
+}
+
+/// List every signal in a directory of XML files, with the file declaring it, its interface and
+/// its body signature.
+///
+/// This selects files with [`read_xml_files`], the walk `#[validate]` does, so given the same
+/// `xml` and `file_filter` arguments it shows which signals the macro can find, e.g. to tell
+/// why a signal is not found. The entries are sorted by file, interface and signal name. Like
+/// [`load_xml_dir`], this includes the interfaces of nested nodes.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::path::Path;
+/// use zbus_lockstep::list_signals;
+///
+/// let signals = list_signals("../xml", None).unwrap();
+///
+/// let (file, _, _, body) = signals
+///     .iter()
+///     .find(|(_, interface, signal, _)| interface == "org.example.Node" && signal == "AddNode")
+///     .unwrap();
+/// assert_eq!(file, Path::new("../xml/test_definition_file.xml"));
+/// assert_eq!(body.to_string(), "(so)");
+/// ```
+pub fn list_signals(
+    xml: impl AsRef<Path>,
+    file_filter: Option<&str>,
+) -> Result<Vec<(PathBuf, String, String, Signature)>> {
+    let mut signals = Vec::new();
+
+    for entry in load_xml_files(xml, file_filter)? {
+        for signal in entry.interface.signals() {
+            signals.push((
+                entry.file.clone(),
+                entry.interface.name().to_string(),
+                signal.name().to_string(),
+                body_signature(signal)?,
+            ));
+        }
+    }
+
+    signals.sort_by(|a, b| (&a.0, &a.1, &a.2).cmp(&(&b.0, &b.1, &b.2)));
+
+    Ok(signals)
+}
+
+/// Validate a signature against the body of a signal declared in a directory of XML files.
+///
+/// This walks and parses `xml_dir` like [`load_xml_dir`], finds `signal` of `interface` and
+/// compares its body to `signature`, as `#[validate]` does. It needs neither the proc-macro nor
+/// a test, so it can run from a build script, to fail the build on drift before any test is
+/// compiled.
+///
+/// # Errors
+///
+/// Fails if the directory cannot be read or parsed, or does not declare the signal. If the
+/// signal's body differs from `signature`, the error is a [`SignatureMismatch`], with the body
+/// as its `expected` signature.
+///
+/// [`SignatureMismatch`]: crate::SignatureMismatch
+///
+/// # Examples
+///
+/// In `build.rs`, with `zbus-lockstep` among the `[build-dependencies]`:
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use zbus_lockstep::validate_signal_signature;
+/// use zvariant::Signature;
+///
+/// fn main() {
+///     println!("cargo:rerun-if-changed=../xml");
+///
+///     let signature = Signature::from_str("(so)").unwrap();
+///     if let Err(e) =
+///         validate_signal_signature("../xml", "org.example.Node", "RemoveNode", &signature)
+///     {
+///         panic!("`RemoveNode` drifted from XML: {e}");
+///     }
+/// }
+/// ```
+pub fn validate_signal_signature(
+    xml_dir: impl AsRef<Path>,
+    interface: &str,
+    signal: &str,
+    signature: &Signature,
+) -> Result<()> {
+    let entries = load_xml_dir(xml_dir)?;
+
+    let mut interfaces = entries
+        .iter()
+        .filter(|entry| entry.interface.name() == interface)
+        .peekable();
+    if interfaces.peek().is_none() {
+        return Err(LockstepError::InterfaceNotFound(interface.to_string()).into());
+    }
+
+    let declared = interfaces
+        .flat_map(|entry| entry.interface.signals())
+        .find(|declared| declared.name() == signal)
+        .ok_or_else(|| LockstepError::MemberNotFound(signal.to_string()))?;
+
+    compare_signatures(&body_signature(declared)?, signature)?;
+
+    Ok(())
+}
+
+/// Validate the types of a signal's fields, given by name, against its body declared in a
+/// directory of XML files.
+///
+/// Like [`validate_signal_signature`], with the signature composed from `type_names` by
+/// [`signature_from_type_names`]. Types registered with [`register_type_signature`], such as
+/// domain-specific wrappers that do not implement [`zvariant::Type`], are looked up in the
+/// registry, before the basic types.
+///
+/// [`signature_from_type_names`]: crate::signature_from_type_names
+/// [`register_type_signature`]: crate::register_type_signature
+///
+/// # Errors
+///
+/// Fails like [`validate_signal_signature`], or if a type name is neither registered nor a
+/// basic type.
+///
+/// # Examples
+///
+/// ```rust
+/// use zbus_lockstep::{register_type_signature, validate_signal_type_names};
+///
+/// register_type_signature("mycrate::NodeName", "s").unwrap();
+///
+/// validate_signal_type_names(
+///     "../xml",
+///     "org.example.Node",
+///     "RemoveNode",
+///     &["mycrate::NodeName", "OwnedObjectPath"],
+/// )
+/// .unwrap();
+/// ```
+pub fn validate_signal_type_names(
+    xml_dir: impl AsRef<Path>,
+    interface: &str,
+    signal: &str,
+    type_names: &[&str],
+) -> Result<()> {
+    let signature = signature_from_type_names(type_names)?;
+
+    validate_signal_signature(xml_dir, interface, signal, &signature)
+}
+
+/// The signature of the arguments of `signal`, taken together.
+fn body_signature(signal: &Signal<'_>) -> Result<Signature> {
+    let body = signal
+        .args()
+        .iter()
+        .map(|arg| arg.ty().to_string())
+        .collect::<String>();
+
+    Ok(Signature::from_str(&body).map_err(|_| "Invalid signature")?)
+}
+
+fn collect_interfaces(
+    node: &Node<'static>,
+    file: &Path,
+    parent_path: Option<&str>,
+    entries: &mut Vec<InterfaceEntry>,
+) {
+    let path = match (parent_path, node.name()) {
+        (_, Some(name)) if name.starts_with('/') => name.to_string(),
+        (Some(parent), Some(name)) => format!("{}/{name}", parent.trim_end_matches('/')),
+        (None, Some(name)) => format!("/{name}"),
+        (Some(parent), None) => parent.to_string(),
+        (None, None) => String::from("/"),
+    };
+
+    for interface in node.interfaces() {
+        entries.push(InterfaceEntry {
+            file: file.to_path_buf(),
+            path: path.clone(),
+            interface: interface.clone(),
+        });
+    }
+
+    for child in node.nodes() {
+        collect_interfaces(child, file, Some(&path), entries);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::{Path, PathBuf};
+
+    use super::wildcard_match;
+    use crate::{
+        find_interface, find_matching_signals, list_signals, load_xml_dir, read_xml_files,
+        register_type_signature, validate_signal_type_names,
+    };
+
+    #[test]
+    fn test_load_xml_dir_walks_nested_nodes() {
+        let interfaces = load_xml_dir("../xml/nested").unwrap();
+
+        let paths: Vec<(&str, String)> = interfaces
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry.interface.name().to_string()))
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec![
+                ("/org/example", String::from("org.example.Root")),
+                ("/org/example/Child", String::from("org.example.Child")),
+                (
+                    "/org/example/Child/Grandchild",
+                    String::from("org.example.Grandchild")
+                ),
+                ("/org/example/Sibling", String::from("org.example.Child")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_interface_by_path() {
+        let interfaces = load_xml_dir("../xml/nested").unwrap();
+
+        let grandchild = find_interface(
+            &interfaces,
+            "/org/example/Child/Grandchild",
+            "org.example.Grandchild",
+        )
+        .unwrap();
+        let signals = grandchild.interface.signals();
+        assert_eq!(signals[0].name(), "Deep");
+
+        // The same interface at different paths can be told apart.
+        let child = find_interface(&interfaces, "/org/example/Child", "org.example.Child").unwrap();
+        let sibling =
+            find_interface(&interfaces, "/org/example/Sibling", "org.example.Child").unwrap();
+        assert_ne!(child.interface, sibling.interface);
+
+        assert!(find_interface(&interfaces, "/", "org.example.Grandchild").is_none());
+    }
+
+    #[test]
+    fn test_find_matching_signals_includes_flat_and_wrapped_bodies() {
+        let matches = find_matching_signals::<(String, u32)>("../xml/wrapping").unwrap();
+
+        assert_eq!(
+            matches,
+            vec![
+                (String::from("org.example.Wrapping"), String::from("Flat")),
+                (
+                    String::from("org.example.Wrapping"),
+                    String::from("Wrapped")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_matching_signals_without_match() {
+        let matches = find_matching_signals::<(bool, bool)>("../xml/wrapping").unwrap();
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_list_signals() {
+        let signals: Vec<(PathBuf, String, String, String)> =
+            list_signals("../xml/all_matches", None)
+                .unwrap()
+                .into_iter()
+                .map(|(file, interface, signal, body)| (file, interface, signal, body.to_string()))
+                .collect();
+
+        let photos = PathBuf::from("../xml/all_matches/org.example.Photos.xml");
+        let stores = PathBuf::from("../xml/all_matches/org.example.Stores.xml");
+        let entry = |file: &PathBuf, interface: &str, body: &str| {
+            (
+                file.clone(),
+                interface.to_string(),
+                String::from("Changed"),
+                body.to_string(),
+            )
+        };
+        assert_eq!(
+            signals,
+            vec![
+                entry(&photos, "org.example.Photos", "(su)"),
+                entry(&stores, "org.example.Books", "(so)"),
+                entry(&stores, "org.example.Music", "(so)"),
+                entry(&stores, "org.example.Videos", "(so)"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_signals_with_file_filter() {
+        let signals = list_signals("../xml/all_matches", Some("*.Photos.*")).unwrap();
+
+        let interfaces: Vec<&str> = signals
+            .iter()
+            .map(|(_, interface, ..)| interface.as_str())
+            .collect();
+        assert_eq!(interfaces, vec!["org.example.Photos"]);
+    }
+
+    #[test]
+    fn test_list_signals_of_glob_pattern() {
+        let signals = list_signals("../xml/glob/**/org.freedesktop.*.xml", None).unwrap();
+
+        let files: Vec<PathBuf> = signals.iter().map(|(file, ..)| file.clone()).collect();
+        assert!(!files.is_empty());
+        assert!(files.iter().all(|file| file
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("org.freedesktop.")));
+    }
+
+    #[test]
+    fn test_read_xml_files_skips_files_without_extension() {
+        let xml_files = read_xml_files("../xml/extensionless", None).unwrap();
+
+        let names: Vec<_> = xml_files
+            .iter()
+            .map(|(path, _)| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["org.example.Plain.xml"]);
+    }
+
+    #[test]
+    fn test_read_xml_files_reads_compressed_files() {
+        let result = read_xml_files("../xml/gzip", None);
+        if cfg!(feature = "gzip") {
+            let xml_files = result.unwrap();
+            assert_eq!(
+                xml_files[0].0,
+                Path::new("../xml/gzip/org.example.Compressed.xml.gz")
+            );
+            assert!(xml_files[0].1.contains(r#"<signal name="Compressed">"#));
+        } else {
+            let err = result.expect_err("compressed XML should need the `gzip` feature");
+            assert_eq!(
+                err.to_string(),
+                "Failed to read XML file: \"../xml/gzip/org.example.Compressed.xml.gz\" Err: \
+                 compressed XML is not supported, enable the `gzip` feature of `zbus-lockstep`, \
+                 or of `zbus-lockstep-macros` for `#[validate]`"
+            );
+        }
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("org.*.xml", "org.example.Node.xml"));
+        assert!(wildcard_match("?.xml", "a.xml"));
+        assert!(!wildcard_match("?.xml", "ab.xml"));
+        assert!(!wildcard_match("org.*", "com.example.xml"));
+    }
+
+    #[test]
+    fn test_validate_signal_type_names_uses_registry() {
+        register_type_signature("xml_dir::test::Path", "o").unwrap();
+
+        validate_signal_type_names(
+            "../xml",
+            "org.example.Node",
+            "AddNode",
+            &["String", "xml_dir::test::Path"],
+        )
+        .unwrap();
+
+        let err = validate_signal_type_names(
+            "../xml",
+            "org.example.Node",
+            "AddNode",
+            &["String", "bool"],
+        )
+        .unwrap_err();
+        assert!(err.downcast_ref::<crate::SignatureMismatch>().is_some());
+    }
+
+    #[test]
+    fn test_list_signals_of_missing_dir() {
+        assert!(list_signals("../xml/missing", None).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+// Tests for `introspect` against a running message bus.
+//
+// Each test re-runs itself on a private session bus, started with `dbus-run-session`, so it
+// does not depend on the bus of the machine it runs on. Without `dbus-run-session`, the tests
+// are skipped.
+#![cfg(feature = "live-introspection")]
+
+use std::{io::ErrorKind, process::Command};
+
+use zbus_lockstep::{introspect, Bus, LockstepError};
+
+// Set for the re-run on the private bus.
+const PRIVATE_BUS: &str = "LOCKSTEP_TEST_PRIVATE_BUS";
+
+/// Whether the test `name` should run in this process. If not, it was run on a private bus,
+/// or skipped.
+fn on_private_bus(name: &str) -> bool {
+    if std::env::var_os(PRIVATE_BUS).is_some() {
+        return true;
+    }
+
+    let output = Command::new("dbus-run-session")
+        .arg("--")
+        .arg(std::env::current_exe().unwrap())
+        .args(["--exact", name, "--test-threads=1"])
+        .env(PRIVATE_BUS, "1")
+        .output();
+    match output {
+        Ok(output) => assert!(
+            output.status.success(),
+            "`{name}` failed on a private bus:\n{}",
+            String::from_utf8_lossy(&output.stdout)
+        ),
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            eprintln!("Skipping `{name}`, `dbus-run-session` is not on `PATH`.");
+        }
+        Err(e) => panic!("Failed to run `dbus-run-session`: {e}"),
+    }
+
+    false
+}
+
+#[test]
+fn test_introspect_bus() {
+    if !on_private_bus("test_introspect_bus") {
+        return;
+    }
+
+    let xml = introspect(
+        Bus::Session,
+        "org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+    )
+    .unwrap();
+    assert!(xml.starts_with('<'));
+    assert!(xml.contains(r#"<interface name="org.freedesktop.DBus.Introspectable">"#));
+}
+
+#[test]
+fn test_introspect_unknown_service() {
+    if !on_private_bus("test_introspect_unknown_service") {
+        return;
+    }
+
+    let err = introspect(Bus::Session, "org.example.Unknown", "/").unwrap_err();
+    match err.downcast_ref::<LockstepError>() {
+        Some(LockstepError::IntrospectionFailed {
+            destination,
+            path,
+            error_name,
+            ..
+        }) => {
+            assert_eq!(destination, "org.example.Unknown");
+            assert_eq!(path, "/");
+            assert_eq!(
+                error_name.as_deref(),
+                Some("org.freedesktop.DBus.Error.ServiceUnknown")
+            );
+        }
+        _ => panic!("unexpected error: {err}"),
+    }
+    assert!(err.to_string().starts_with(
+        "Failed to introspect \"/\" of \"org.example.Unknown\": \
+         org.freedesktop.DBus.Error.ServiceUnknown: "
+    ));
+}
@@ -4,11 +4,17 @@
 // - `method_args_signature`
 // - `signal_body_type_signature`
 // - `property_type_signature`
+// - `assert_same_signature`
+// - `assert_different_signature`
+// - `assert_eq_signatures`
+// - `assert_signals_same_body`
 
 use zbus_lockstep::{
-    method_args_signature, method_return_signature, property_type_signature,
-    signal_body_type_signature,
+    assert_different_signature, assert_eq_signatures, assert_same_signature,
+    assert_signals_same_body, method_args_signature, method_return_signature,
+    property_type_signature, signal_body_type_signature, CompareOptions,
 };
+use zvariant::{OwnedObjectPath, OwnedValue, Type};
 
 #[test]
 fn test_method_return_signature() {
@@ -41,3 +47,119 @@ fn test_property_type_signature() {
     let signature = property_type_signature!("Features");
     assert_eq!(signature, "as");
 }
+
+#[derive(Type)]
+#[allow(dead_code)]
+struct AddNode {
+    name: String,
+    path: OwnedObjectPath,
+}
+
+#[derive(Type)]
+#[allow(dead_code)]
+struct RemoveNode {
+    name: String,
+    path: OwnedObjectPath,
+}
+
+#[derive(Type)]
+#[allow(dead_code)]
+struct Alert {
+    urgent: bool,
+    color: String,
+    volume: f64,
+}
+
+#[test]
+fn test_assert_same_signature() {
+    assert_same_signature!(AddNode, RemoveNode);
+    assert_same_signature!((String, OwnedObjectPath), AddNode);
+}
+
+#[should_panic]
+#[test]
+fn test_assert_same_signature_with_different_signatures() {
+    assert_same_signature!(AddNode, Alert);
+}
+
+#[test]
+fn test_assert_different_signature() {
+    assert_different_signature!(AddNode, Alert);
+}
+
+#[should_panic]
+#[test]
+fn test_assert_different_signature_with_same_signatures() {
+    assert_different_signature!(AddNode, RemoveNode);
+}
+
+#[test]
+fn test_assert_eq_signatures() {
+    assert_eq_signatures!(AddNode::SIGNATURE, RemoveNode::SIGNATURE);
+    assert_eq_signatures!(AddNode::SIGNATURE, "(so)");
+}
+
+#[test]
+fn test_assert_eq_signatures_points_at_divergence() {
+    let panic = std::panic::catch_unwind(|| {
+        assert_eq_signatures!("(sa{sv}as)", "(sa{sv}ao)");
+    })
+    .expect_err("differing signatures should panic");
+    let msg = panic
+        .downcast_ref::<String>()
+        .expect("panic message should be a String");
+
+    let lines: Vec<&str> = msg.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "Signatures differ: expected signature \"(sa{sv}as)\", found \"(sa{sv}ao)\", \
+             first differing at field 2.",
+            "left:  (sa{sv}as)",
+            "right: (sa{sv}ao)",
+            "               ^",
+        ]
+    );
+
+    // The caret is under the first differing character of both signatures.
+    let column = lines[3].find('^').unwrap();
+    assert_eq!(&lines[1][column..=column], "s");
+    assert_eq!(&lines[2][column..=column], "o");
+}
+
+#[test]
+fn test_assert_eq_signatures_with_options() {
+    let options = CompareOptions {
+        allow_value_fields: true,
+        ..Default::default()
+    };
+    assert_eq_signatures!("(so)", <(String, OwnedValue)>::SIGNATURE, options);
+}
+
+#[test]
+#[should_panic(
+    expected = "Signatures differ: expected signature \"(so)\", found \"(sv)\", \
+                           first differing at field 1."
+)]
+fn test_assert_eq_signatures_without_options() {
+    assert_eq_signatures!("(so)", <(String, OwnedValue)>::SIGNATURE);
+}
+
+#[test]
+fn test_assert_signals_same_body() {
+    assert_signals_same_body!(
+        "../xml/test_definition_file.xml",
+        "org.example.Node",
+        ["AddNode", "RemoveNode"]
+    );
+}
+
+#[should_panic(expected = "Signal \"Alert\" has body \"(bsd)\", but \"AddNode\" has body \"(so)\"")]
+#[test]
+fn test_assert_signals_same_body_reports_divergent_signal() {
+    assert_signals_same_body!(
+        "../xml/test_definition_file.xml",
+        "org.example.Node",
+        ["AddNode", "RemoveNode", "Alert", "Ping"]
+    );
+}
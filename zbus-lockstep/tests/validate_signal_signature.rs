@@ -0,0 +1,63 @@
+// Tests for `validate_signal_signature`, which validates a signature against XML without the
+// proc-macro, e.g. from a build script.
+
+use std::str::FromStr;
+
+use zbus_lockstep::{validate_signal_signature, LockstepError, SignatureMismatch};
+use zvariant::{OwnedObjectPath, Signature, Type};
+
+#[test]
+fn test_validate_signal_signature() {
+    validate_signal_signature(
+        "../xml",
+        "org.example.Node",
+        "RemoveNode",
+        <(String, OwnedObjectPath)>::SIGNATURE,
+    )
+    .unwrap();
+
+    // The flat body of the signal matches a struct of the same fields.
+    let signature = Signature::from_str("(so)").unwrap();
+    validate_signal_signature("../xml", "org.example.Node", "RemoveNode", &signature).unwrap();
+}
+
+#[test]
+fn test_validate_signal_signature_mismatch() {
+    let signature = Signature::from_str("(su)").unwrap();
+    let err = validate_signal_signature("../xml", "org.example.Node", "RemoveNode", &signature)
+        .unwrap_err();
+
+    let mismatch = err
+        .downcast_ref::<SignatureMismatch>()
+        .expect("a differing body should be a `SignatureMismatch`");
+    assert_eq!(mismatch.expected.to_string(), "(so)");
+    assert_eq!(mismatch.actual.to_string(), "(su)");
+    assert_eq!(mismatch.first_diff_index, Some(1));
+}
+
+#[test]
+fn test_validate_signal_signature_not_found() {
+    let signature = Signature::from_str("(so)").unwrap();
+
+    let err = validate_signal_signature("../xml", "org.example.Missing", "RemoveNode", &signature)
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<LockstepError>(),
+        Some(LockstepError::InterfaceNotFound(name)) if name == "org.example.Missing"
+    ));
+
+    let err =
+        validate_signal_signature("../xml", "org.example.Node", "Missing", &signature).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<LockstepError>(),
+        Some(LockstepError::MemberNotFound(name)) if name == "Missing"
+    ));
+
+    assert!(validate_signal_signature(
+        "../xml/missing",
+        "org.example.Node",
+        "RemoveNode",
+        &signature
+    )
+    .is_err());
+}
@@ -0,0 +1,29 @@
+// Tests for the environment variables overriding the XML path. Each sets the process
+// environment, so they run one after the other in a single test.
+
+use std::path::Path;
+
+use zbus_lockstep::resolve_xml_paths;
+
+#[test]
+fn test_xml_path_env_precedence() {
+    let canonical = |path: &str| Path::new(path).canonicalize().unwrap();
+
+    // The earlier name is read on its own.
+    std::env::set_var("LOCKSTEP_XML_PATH", "../xml/methods");
+    assert_eq!(
+        resolve_xml_paths(None).unwrap(),
+        vec![canonical("../xml/methods")]
+    );
+
+    // `ZBUS_LOCKSTEP_XML_PATH` takes precedence over it.
+    std::env::set_var("ZBUS_LOCKSTEP_XML_PATH", "../xml");
+    assert_eq!(resolve_xml_paths(None).unwrap(), vec![canonical("../xml")]);
+
+    // Errors name the variable that was read.
+    std::env::set_var("ZBUS_LOCKSTEP_XML_PATH", "../xml/does_not_exist");
+    let err = resolve_xml_paths(None).unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("`ZBUS_LOCKSTEP_XML_PATH` entry \"../xml/does_not_exist\" not found"));
+}